@@ -0,0 +1,63 @@
+//! Measures the cost of creating a GraphViz context.
+//!
+//! `Context::new` reads the installed plugin configuration, while
+//! `Context::new_with_plugins(true, false)` registers only the built-in
+//! plugins. Run with `cargo bench --bench context`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use vizoxide::layout::{apply_layout, Engine};
+use vizoxide::render::{render_to_bytes, Format};
+use vizoxide::{Context, Graph, GraphvizError};
+
+/// Number of timed iterations per measurement.
+const ITERATIONS: u32 = 50;
+
+/// Times a closure over `ITERATIONS` runs after one warm-up run.
+fn measure<F>(name: &str, mut run: F) -> Result<Duration, GraphvizError>
+where
+    F: FnMut() -> Result<(), GraphvizError>,
+{
+    run()?;
+    
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        run()?;
+    }
+    let per_iteration = start.elapsed() / ITERATIONS;
+    
+    println!("{:<40} {:>12.3?} per iteration", name, per_iteration);
+    Ok(per_iteration)
+}
+
+/// Lays out and renders a two-node graph with the given context.
+fn render_small_graph(context: &Context) -> Result<(), GraphvizError> {
+    let mut graph = Graph::from_edges("bench", true, [("a", "b")])?;
+    apply_layout(context, &mut graph, Engine::Dot)?;
+    black_box(render_to_bytes(context, &graph, Format::Svg)?);
+    Ok(())
+}
+
+fn main() -> Result<(), GraphvizError> {
+    let discovered = measure("Context::new", || {
+        black_box(Context::new()?);
+        Ok(())
+    })?;
+    let builtin = measure("Context::new_with_plugins(true, false)", || {
+        black_box(Context::new_with_plugins(true, false)?);
+        Ok(())
+    })?;
+    
+    measure("Context::new + render", || render_small_graph(&Context::new()?))?;
+    
+    let shared = Context::new()?;
+    measure("shared context, render only", || render_small_graph(&shared))?;
+    
+    println!(
+        "plugin discovery costs {:.1}x a built-in-only context",
+        discovered.as_secs_f64() / builtin.as_secs_f64().max(f64::EPSILON),
+    );
+    
+    Ok(())
+}
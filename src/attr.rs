@@ -30,6 +30,17 @@ pub trait AttributeContainer {
     /// Option containing the attribute value if it exists
     fn get_attribute(&self, name: &str) -> Result<Option<String>, GraphvizError>;
     
+    /// Gets the attributes set on the container itself.
+    ///
+    /// For nodes and edges, values inherited from the graph's default node
+    /// or edge attributes are not included. For graphs, every attribute with
+    /// a non-empty value is included.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the attribute name/value pairs or an error
+    fn local_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError>;
+    
     /// Checks if an attribute exists on the container.
     ///
     /// # Arguments
@@ -78,6 +89,276 @@ pub trait AttributeContainer {
         }
         Ok(())
     }
+    
+    /// Copies the attributes set on another container onto this one.
+    ///
+    /// Only the attributes returned by `local_attributes` are copied, so
+    /// values the source merely inherits from defaults are not turned into
+    /// explicit values here. Attributes this container sets that the source
+    /// does not are kept. Copying between graphs of different root graphs
+    /// works, since attributes are declared as needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The container to copy attributes from
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    fn copy_attributes_from(&self, other: &Self) -> Result<(), GraphvizError> {
+        for (name, value) in other.local_attributes()? {
+            self.set_attribute(&name, &value)?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Sets the label and verifies that it reads back unchanged.
+    ///
+    /// Labels are passed to GraphViz as UTF-8, which matches its default
+    /// `charset`; if the graph sets `charset` to `latin1`, non-ASCII labels
+    /// will not round-trip. Note that whether a character is drawn still
+    /// depends on the font providing a glyph for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label text
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success, or `GraphvizError::AttributeSetFailed` if the
+    /// stored label differs from the one given
+    fn set_label_checked(&self, label: &str) -> Result<(), GraphvizError> {
+        self.set_attribute("label", label)?;
+        
+        match self.get_attribute("label")? {
+            Some(stored) if stored == label => Ok(()),
+            _ => Err(GraphvizError::AttributeSetFailed),
+        }
+    }
+    
+    /// Adds or removes a single flag in the comma-separated `style` attribute.
+    ///
+    /// Other tokens already present, including custom ones such as
+    /// `setlinewidth(2)`, are kept in their original order. Adding a flag that
+    /// is already set or removing one that is absent leaves `style` unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - The style flag, e.g. "bold" or "filled"
+    /// * `on` - Whether the flag should be present
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    fn toggle_style(&self, style: &str, on: bool) -> Result<(), GraphvizError> {
+        let current = self.get_attribute("style")?.unwrap_or_default();
+        let mut tokens = split_style(&current);
+        let present = tokens.contains(&style);
+        
+        if on == present {
+            return Ok(());
+        }
+        
+        if on {
+            tokens.push(style);
+        } else {
+            tokens.retain(|token| *token != style);
+        }
+        
+        self.set_attribute("style", &tokens.join(","))
+    }
+    
+    /// Adds a single flag to the `style` attribute, keeping existing flags.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - The style flag to add
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    fn add_style(&self, style: &str) -> Result<(), GraphvizError> {
+        self.toggle_style(style, true)
+    }
+    
+    /// Removes a single flag from the `style` attribute, keeping the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - The style flag to remove
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    fn remove_style(&self, style: &str) -> Result<(), GraphvizError> {
+        self.toggle_style(style, false)
+    }
+}
+
+/// Splits a `style` value into its trimmed, non-empty tokens.
+///
+/// Commas inside parentheses, as in `setlinewidth(1,2)`, do not split tokens.
+fn split_style(style: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    
+    for (i, c) in style.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                tokens.push(style[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    
+    tokens.push(style[start..].trim());
+    tokens.retain(|token| !token.is_empty());
+    
+    tokens
+}
+
+/// Escapes text for use as a `label` attribute value.
+///
+/// GraphViz interprets backslash sequences such as `\n`, `\l`, and `\N` in
+/// labels. This doubles literal backslashes so they are drawn as-is, and turns
+/// real line breaks into centered `\n` breaks.
+///
+/// # Arguments
+///
+/// * `s` - The label text
+///
+/// # Returns
+///
+/// The escaped label value
+pub fn escape_label(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    
+    escaped
+}
+
+/// Builds a multi-line `label` value with every line justified the same way.
+///
+/// Each line ends with the GraphViz break for the requested justification
+/// (`\l`, `\n` or `\r`). Line content is escaped with `escape_label`, so literal
+/// backslashes are never read as break codes; real line breaks inside a line
+/// start a new line with the same justification.
+///
+/// # Arguments
+///
+/// * `lines` - The label lines, top to bottom
+/// * `justify` - The horizontal justification of each line
+///
+/// # Returns
+///
+/// The escaped label value
+pub fn multiline_label(lines: &[&str], justify: LabelJust) -> String {
+    let terminator = match justify {
+        LabelJust::Left => "\\l",
+        LabelJust::Center => "\\n",
+        LabelJust::Right => "\\r",
+    };
+    let mut label = String::new();
+    
+    for line in lines.iter().flat_map(|line| line.split('\n')) {
+        label.push_str(&escape_label(line));
+        label.push_str(terminator);
+    }
+    
+    label
+}
+
+/// Formats a string as a DOT identifier, quoting it if necessary.
+///
+/// Names that are plain identifiers or numerals are returned unchanged. Anything
+/// else (spaces, punctuation such as `:` which DOT reads as a port separator, or
+/// keywords like `node`) is wrapped in double quotes with embedded quotes escaped.
+///
+/// Inside a quoted string the DOT parser reads `\\` as a literal pair of
+/// backslashes, `\"` as a quote, and a backslash before a newline as a line
+/// continuation. An odd run of backslashes directly before a `"`, a newline,
+/// or the end of the string therefore has no quoted form and is rejected.
+///
+/// # Arguments
+///
+/// * `s` - The identifier
+///
+/// # Returns
+///
+/// A Result containing the identifier, quoted if needed, or
+/// `GraphvizError::UnquotableId` if it cannot be written as DOT
+pub fn quote_id(s: &str) -> Result<String, GraphvizError> {
+    if is_plain_id(s) || is_numeral(s) {
+        return Ok(s.to_owned());
+    }
+    
+    let mut quoted = String::with_capacity(s.len() + 2);
+    let mut backslashes = 0;
+    quoted.push('"');
+    
+    for c in s.chars() {
+        if matches!(c, '"' | '\n') && backslashes % 2 == 1 {
+            return Err(GraphvizError::UnquotableId(s.to_owned()));
+        }
+        
+        if c == '"' {
+            quoted.push('\\');
+        }
+        
+        backslashes = if c == '\\' { backslashes + 1 } else { 0 };
+        quoted.push(c);
+    }
+    
+    if backslashes % 2 == 1 {
+        return Err(GraphvizError::UnquotableId(s.to_owned()));
+    }
+    
+    quoted.push('"');
+    Ok(quoted)
+}
+
+/// Checks whether a string is a DOT identifier that needs no quoting.
+fn is_plain_id(s: &str) -> bool {
+    const KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+    
+    let mut chars = s.chars();
+    let starts_well = match chars.next() {
+        Some(c) => c.is_ascii_alphabetic() || c == '_' || !c.is_ascii(),
+        None => false,
+    };
+    
+    starts_well
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii())
+        && !KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(s))
+}
+
+/// Checks whether a string is a DOT numeral such as `-1`, `.5`, or `2.75`.
+fn is_numeral(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (digits, None),
+    };
+    
+    let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    
+    match fraction {
+        Some(fraction) => all_digits(whole) && all_digits(fraction) && !(whole.is_empty() && fraction.is_empty()),
+        None => !whole.is_empty() && all_digits(whole),
+    }
 }
 
 /// Common GraphViz attribute names for graphs.
@@ -94,10 +375,18 @@ pub mod graph {
     pub const FONTSIZE: &str = "fontsize";
     /// The font color for labels.
     pub const FONTCOLOR: &str = "fontcolor";
+    /// The directories searched for font files.
+    pub const FONTPATH: &str = "fontpath";
     /// The graph's label.
     pub const LABEL: &str = "label";
+    /// The vertical placement of the graph label.
+    pub const LABELLOC: &str = "labelloc";
+    /// The horizontal justification of the graph label.
+    pub const LABELJUST: &str = "labeljust";
     /// The background color of the graph.
     pub const BGCOLOR: &str = "bgcolor";
+    /// Xdot drawing operations rendered behind the graph.
+    pub const BACKGROUND: &str = "_background";
     /// Control the width of the page for pagination.
     pub const PAGE: &str = "page";
     /// Control the margin around the graph.
@@ -110,6 +399,10 @@ pub mod graph {
     pub const URL: &str = "URL";
     /// The ordering of nodes.
     pub const ORDERING: &str = "ordering";
+    /// The rank constraint on a subgraph's nodes.
+    pub const RANK: &str = "rank";
+    /// Whether every external label is drawn, even if it overlaps other elements.
+    pub const FORCELABELS: &str = "forcelabels";
     /// The rank separation between nodes.
     pub const RANKSEP: &str = "ranksep";
     /// The node separation within a rank.
@@ -132,6 +425,13 @@ pub mod graph {
     pub const SPLINES: &str = "splines";
     /// The overlap removal algorithm.
     pub const OVERLAP: &str = "overlap";
+    /// Every graph attribute name defined in this module.
+    pub const ALL: &[&str] = &[
+        RANKDIR, SIZE, RATIO, FONTNAME, FONTSIZE, FONTCOLOR, FONTPATH, LABEL, LABELLOC,
+        LABELJUST, BGCOLOR, BACKGROUND, PAGE, MARGIN, STYLE, CONCENTRATE, URL, ORDERING,
+        RANK, FORCELABELS, RANKSEP, NODESEP, EDGE_COLOR, EDGE_STYLE, EDGE_DIR, NODE_COLOR, NODE_STYLE,
+        NODE_SHAPE, ROTATE, SPLINES, OVERLAP,
+    ];
 }
 
 /// Common GraphViz attribute names for nodes.
@@ -140,6 +440,8 @@ pub mod node {
     pub const SHAPE: &str = "shape";
     /// The label of the node.
     pub const LABEL: &str = "label";
+    /// An external label drawn near the node without affecting its size.
+    pub const XLABEL: &str = "xlabel";
     /// The color of the node.
     pub const COLOR: &str = "color";
     /// The fill color of the node.
@@ -160,6 +462,8 @@ pub mod node {
     pub const FIXEDSIZE: &str = "fixedsize";
     /// The URL to associate with the node.
     pub const URL: &str = "URL";
+    /// The browser window or frame in which the node's link opens.
+    pub const TARGET: &str = "target";
     /// The tooltip for the node.
     pub const TOOLTIP: &str = "tooltip";
     /// Position of the node.
@@ -168,6 +472,8 @@ pub mod node {
     pub const GROUP: &str = "group";
     /// The image file to display in the node.
     pub const IMAGE: &str = "image";
+    /// How the image is scaled to fit the node.
+    pub const IMAGESCALE: &str = "imagescale";
     /// The shape's distortion.
     pub const DISTORTION: &str = "distortion";
     /// The shape's skew.
@@ -180,6 +486,18 @@ pub mod node {
     pub const ORIENTATION: &str = "orientation";
     /// The peripheries count for the node.
     pub const PERIPHERIES: &str = "peripheries";
+    /// The edge ordering constraint for the node.
+    pub const ORDERING: &str = "ordering";
+    /// The identifier emitted as the SVG element `id`.
+    pub const ID: &str = "id";
+    /// The class names emitted as the SVG element `class`.
+    pub const CLASS: &str = "class";
+    /// Every node attribute name defined in this module.
+    pub const ALL: &[&str] = &[
+        SHAPE, LABEL, XLABEL, COLOR, FILLCOLOR, STYLE, FONTNAME, FONTSIZE, FONTCOLOR, WIDTH,
+        HEIGHT, FIXEDSIZE, URL, TARGET, TOOLTIP, POS, GROUP, IMAGE, IMAGESCALE, DISTORTION, SKEW, PENWIDTH,
+        SIDES, ORIENTATION, PERIPHERIES, ORDERING, ID, CLASS,
+    ];
 }
 
 /// Common GraphViz attribute names for edges.
@@ -204,6 +522,8 @@ pub mod edge {
     pub const MINLEN: &str = "minlen";
     /// The URL to associate with the edge.
     pub const URL: &str = "URL";
+    /// The browser window or frame in which the edge's link opens.
+    pub const TARGET: &str = "target";
     /// The tooltip for the edge.
     pub const TOOLTIP: &str = "tooltip";
     /// Whether to constrain the edge.
@@ -230,6 +550,23 @@ pub mod edge {
     pub const POS: &str = "pos";
     /// The label position of the edge.
     pub const LPOS: &str = "lp";
+    /// The label placed near the head of the edge.
+    pub const HEADLABEL: &str = "headlabel";
+    /// The label placed near the tail of the edge.
+    pub const TAILLABEL: &str = "taillabel";
+    /// The label placed outside the edge after layout.
+    pub const XLABEL: &str = "xlabel";
+    /// The identifier emitted as the SVG element `id`.
+    pub const ID: &str = "id";
+    /// The class names emitted as the SVG element `class`.
+    pub const CLASS: &str = "class";
+    /// Every edge attribute name defined in this module.
+    pub const ALL: &[&str] = &[
+        LABEL, COLOR, STYLE, DIR, FONTNAME, FONTSIZE, FONTCOLOR, WEIGHT, MINLEN, URL, TARGET,
+        TOOLTIP, CONSTRAINT, PENWIDTH, LABELANGLE, LABELDISTANCE, LABELTOOLTIP, DECORATE,
+        TAILPORT, HEADPORT, ARROWHEAD, ARROWTAIL, POS, LPOS, HEADLABEL, TAILLABEL, XLABEL, ID,
+        CLASS,
+    ];
 }
 
 /// Common GraphViz attribute values.
@@ -332,6 +669,20 @@ pub mod values {
         pub const NONE: &str = "none";
     }
 
+    /// Common values for the subgraph rank constraint.
+    pub mod rank {
+        /// All nodes on the same rank.
+        pub const SAME: &str = "same";
+        /// All nodes on the minimum rank.
+        pub const MIN: &str = "min";
+        /// All nodes on the maximum rank.
+        pub const MAX: &str = "max";
+        /// All nodes on the minimum rank, and only these.
+        pub const SOURCE: &str = "source";
+        /// All nodes on the maximum rank, and only these.
+        pub const SINK: &str = "sink";
+    }
+
     /// Common values for graph rank direction.
     pub mod rankdir {
         /// Top to bottom direction.
@@ -406,3 +757,429 @@ pub mod values {
         pub const FDP: &str = "fdp";
     }
 }
+
+/// Vertical placement of a graph or cluster label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelLoc {
+    /// Place the label at the top.
+    Top,
+    /// Center the label vertically (only meaningful for nodes).
+    Center,
+    /// Place the label at the bottom.
+    Bottom,
+}
+
+impl LabelLoc {
+    /// Gets the GraphViz value for the `labelloc` attribute.
+    ///
+    /// # Returns
+    ///
+    /// The attribute value as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LabelLoc::Top => "t",
+            LabelLoc::Center => "c",
+            LabelLoc::Bottom => "b",
+        }
+    }
+}
+
+/// Horizontal justification of a graph or cluster label, or of the lines in a label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelJust {
+    /// Left-justify the label.
+    Left,
+    /// Center the label.
+    Center,
+    /// Right-justify the label.
+    Right,
+}
+
+impl LabelJust {
+    /// Gets the GraphViz value for the `labeljust` attribute.
+    ///
+    /// # Returns
+    ///
+    /// The attribute value as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LabelJust::Left => "l",
+            LabelJust::Center => "c",
+            LabelJust::Right => "r",
+        }
+    }
+}
+
+/// Edge ordering constraint for the `ordering` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ordering {
+    /// Keep incoming edges in the order they were created.
+    In,
+    /// Keep outgoing edges in the order they were created.
+    Out,
+}
+
+impl Ordering {
+    /// Gets the GraphViz value for the `ordering` attribute.
+    ///
+    /// # Returns
+    ///
+    /// The attribute value as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ordering::In => "in",
+            Ordering::Out => "out",
+        }
+    }
+}
+
+/// Image scaling for the `imagescale` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageScale {
+    /// Draw the image at its natural size, growing the node to contain it (the default).
+    None,
+    /// Scale the image to fit the node, preserving its aspect ratio.
+    Fit,
+    /// Stretch the image to the node's width only.
+    Width,
+    /// Stretch the image to the node's height only.
+    Height,
+    /// Stretch the image to fill the node in both dimensions.
+    Both,
+}
+
+impl ImageScale {
+    /// Gets the GraphViz value for the `imagescale` attribute.
+    ///
+    /// # Returns
+    ///
+    /// The attribute value as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageScale::None => "false",
+            ImageScale::Fit => "true",
+            ImageScale::Width => "width",
+            ImageScale::Height => "height",
+            ImageScale::Both => "both",
+        }
+    }
+}
+
+/// Direction of graph layout for the `rankdir` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankDir {
+    /// Top to bottom.
+    TopToBottom,
+    /// Left to right.
+    LeftToRight,
+    /// Bottom to top.
+    BottomToTop,
+    /// Right to left.
+    RightToLeft,
+}
+
+impl RankDir {
+    /// Gets the GraphViz value for the `rankdir` attribute.
+    ///
+    /// # Returns
+    ///
+    /// The attribute value as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RankDir::TopToBottom => values::rankdir::TB,
+            RankDir::LeftToRight => values::rankdir::LR,
+            RankDir::BottomToTop => values::rankdir::BT,
+            RankDir::RightToLeft => values::rankdir::RL,
+        }
+    }
+    
+    /// Parses a `rankdir` attribute value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// The direction, or None if GraphViz would not recognize the value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            values::rankdir::TB => Some(RankDir::TopToBottom),
+            values::rankdir::LR => Some(RankDir::LeftToRight),
+            values::rankdir::BT => Some(RankDir::BottomToTop),
+            values::rankdir::RL => Some(RankDir::RightToLeft),
+            _ => None,
+        }
+    }
+}
+
+/// Edge routing style for the `splines` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Splines {
+    /// No edges are drawn.
+    None,
+    /// Straight line segments between endpoints.
+    Line,
+    /// Polylines that avoid nodes.
+    Polyline,
+    /// Curved arcs.
+    Curved,
+    /// Axis-aligned segments.
+    Ortho,
+    /// Smooth splines that avoid nodes (the default).
+    Spline,
+}
+
+impl Splines {
+    /// Gets the GraphViz value for the `splines` attribute.
+    ///
+    /// # Returns
+    ///
+    /// The attribute value as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Splines::None => values::splines::NONE,
+            Splines::Line => values::splines::LINE,
+            Splines::Polyline => values::splines::POLYLINE,
+            Splines::Curved => values::splines::CURVED,
+            Splines::Ortho => values::splines::ORTHO,
+            Splines::Spline => values::splines::SPLINE,
+        }
+    }
+    
+    /// Parses a `splines` attribute value.
+    ///
+    /// The boolean aliases are accepted: "true" means `Spline` and "false"
+    /// means `Line`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// The routing style, or None if GraphViz would not recognize the value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            values::splines::NONE => Some(Splines::None),
+            values::splines::LINE | values::splines::FALSE => Some(Splines::Line),
+            values::splines::POLYLINE => Some(Splines::Polyline),
+            values::splines::CURVED => Some(Splines::Curved),
+            values::splines::ORTHO => Some(Splines::Ortho),
+            values::splines::SPLINE | values::splines::TRUE => Some(Splines::Spline),
+            _ => None,
+        }
+    }
+}
+
+/// A primitive arrow shape used to compose an `ArrowType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowShape {
+    /// A filled triangle pointing at the node.
+    Normal,
+    /// A triangle pointing away from the node.
+    Inv,
+    /// A square.
+    Box,
+    /// A crow's foot.
+    Crow,
+    /// A rhombus.
+    Diamond,
+    /// A circle.
+    Dot,
+    /// A bar perpendicular to the edge.
+    Tee,
+    /// An arrowhead with a notch.
+    Vee,
+    /// A curve bending towards the node.
+    Curve,
+    /// A curve bending away from the node.
+    ICurve,
+    /// No shape; useful as a spacer between other shapes.
+    None,
+}
+
+impl ArrowShape {
+    /// Gets the GraphViz name of the shape.
+    ///
+    /// # Returns
+    ///
+    /// The shape name as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArrowShape::Normal => values::arrowhead::NORMAL,
+            ArrowShape::Inv => values::arrowhead::INV,
+            ArrowShape::Box => values::arrowhead::BOX,
+            ArrowShape::Crow => values::arrowhead::CROW,
+            ArrowShape::Diamond => values::arrowhead::DIAMOND,
+            ArrowShape::Dot => values::arrowhead::DOT,
+            ArrowShape::Tee => values::arrowhead::TEE,
+            ArrowShape::Vee => values::arrowhead::VEE,
+            ArrowShape::Curve => "curve",
+            ArrowShape::ICurve => "icurve",
+            ArrowShape::None => values::arrowhead::NONE,
+        }
+    }
+}
+
+/// Which side of the edge an arrow shape is drawn on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowSide {
+    /// Both sides of the edge.
+    Both,
+    /// Only the left side of the edge (the `l` modifier).
+    Left,
+    /// Only the right side of the edge (the `r` modifier).
+    Right,
+}
+
+/// A composite arrow for the `arrowhead` and `arrowtail` attributes.
+///
+/// GraphViz arrows are built from up to four primitive shapes, drawn starting
+/// at the node, each optionally open (unfilled) and clipped to one side of the
+/// edge. For example, an open diamond followed by a tee gives `odiamondtee`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArrowType {
+    /// The primitive shapes with their open flag and side
+    parts: Vec<(ArrowShape, bool, ArrowSide)>,
+}
+
+impl ArrowType {
+    /// The maximum number of primitive shapes GraphViz draws in one arrow.
+    pub const MAX_SHAPES: usize = 4;
+    
+    /// Creates an empty arrow.
+    ///
+    /// # Returns
+    ///
+    /// A new ArrowType without shapes
+    pub fn new() -> Self {
+        Default::default()
+    }
+    
+    /// Appends a filled shape drawn on both sides of the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - The shape to append
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn shape(self, shape: ArrowShape) -> Self {
+        self.modified_shape(shape, false, ArrowSide::Both)
+    }
+    
+    /// Appends an open (unfilled) shape drawn on both sides of the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - The shape to append
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn open_shape(self, shape: ArrowShape) -> Self {
+        self.modified_shape(shape, true, ArrowSide::Both)
+    }
+    
+    /// Appends a shape with explicit modifiers.
+    ///
+    /// Shapes beyond `MAX_SHAPES` are ignored, as GraphViz would ignore them.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - The shape to append
+    /// * `open` - Whether the shape is drawn unfilled
+    /// * `side` - Which side of the edge the shape is drawn on
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn modified_shape(mut self, shape: ArrowShape, open: bool, side: ArrowSide) -> Self {
+        if self.parts.len() < Self::MAX_SHAPES {
+            self.parts.push((shape, open, side));
+        }
+        self
+    }
+    
+    /// Converts the arrow to its attribute value.
+    ///
+    /// # Returns
+    ///
+    /// The arrow specification as a string, `normal` if no shape was added
+    pub fn to_attribute(&self) -> String {
+        if self.parts.is_empty() {
+            return values::arrowhead::NORMAL.to_owned();
+        }
+        
+        let mut spec = String::new();
+        
+        for (shape, open, side) in &self.parts {
+            if *open {
+                spec.push('o');
+            }
+            
+            match side {
+                ArrowSide::Both => {}
+                ArrowSide::Left => spec.push('l'),
+                ArrowSide::Right => spec.push('r'),
+            }
+            
+            spec.push_str(shape.as_str());
+        }
+        
+        spec
+    }
+}
+
+/// A color and font scheme applied to a whole graph by `Graph::apply_theme`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Background color of the drawing
+    pub background: String,
+    /// Fill color of nodes
+    pub node_fill: String,
+    /// Border color of nodes
+    pub node_border: String,
+    /// Color of edges and their arrowheads
+    pub edge_color: String,
+    /// Font used for graph, node and edge labels
+    pub font_name: String,
+    /// Color of graph, node and edge labels
+    pub font_color: String,
+}
+
+impl Theme {
+    /// Creates a theme with light text and lines on a dark background.
+    ///
+    /// # Returns
+    ///
+    /// The dark Theme
+    pub fn dark() -> Self {
+        Theme {
+            background: "#1e1e1e".to_owned(),
+            node_fill: "#2d2d30".to_owned(),
+            node_border: "#c8c8c8".to_owned(),
+            edge_color: "#a0a0a0".to_owned(),
+            font_name: "Helvetica".to_owned(),
+            font_color: "#e0e0e0".to_owned(),
+        }
+    }
+    
+    /// Creates a theme with dark text and lines on a white background.
+    ///
+    /// # Returns
+    ///
+    /// The light Theme
+    pub fn light() -> Self {
+        Theme {
+            background: "#ffffff".to_owned(),
+            node_fill: "#f3f3f3".to_owned(),
+            node_border: "#333333".to_owned(),
+            edge_color: "#555555".to_owned(),
+            font_name: "Helvetica".to_owned(),
+            font_color: "#222222".to_owned(),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Plain-data representation of graph structure.
+//!
+//! This module provides `GraphData`, a pointer-free snapshot of a graph's nodes,
+//! edges, and attributes that can be stored or embedded in other documents. With
+//! the `serde` feature enabled, `GraphData` and `Graph` implement `Serialize` and
+//! `Deserialize`, with graphs converted through `GraphData`.
+
+use std::collections::BTreeMap;
+
+use crate::attr::AttributeContainer;
+use crate::error::GraphvizError;
+use crate::graph::Graph;
+
+/// A snapshot of a graph's structure and attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphData {
+    /// Name of the graph
+    pub name: String,
+    /// Whether the graph is directed
+    pub directed: bool,
+    /// Whether the graph is strict (no duplicate edges)
+    pub strict: bool,
+    /// Graph-level attributes
+    pub attributes: BTreeMap<String, String>,
+    /// Nodes in iteration order
+    pub nodes: Vec<NodeData>,
+    /// Edges in iteration order
+    pub edges: Vec<EdgeData>,
+}
+
+/// A snapshot of a single node.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeData {
+    /// Name of the node
+    pub name: String,
+    /// Attributes set on the node
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// A snapshot of a single edge.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeData {
+    /// Name of the source node
+    pub from: String,
+    /// Name of the target node
+    pub to: String,
+    /// Optional name of the edge
+    pub name: Option<String>,
+    /// Attributes set on the edge
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl GraphData {
+    /// Captures the structure and attributes of a graph.
+    ///
+    /// Only attributes that differ from their declared defaults are recorded
+    /// for nodes and edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to capture
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the GraphData or an error
+    pub fn from_graph(graph: &Graph) -> Result<Self, GraphvizError> {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        
+        for node in graph.nodes() {
+            nodes.push(NodeData {
+                name: node.name()?,
+                attributes: node.local_attributes()?.into_iter().collect(),
+            });
+            
+            for edge in graph.out_edges(&node) {
+                edges.push(EdgeData {
+                    from: edge.tail().name()?,
+                    to: edge.head().name()?,
+                    name: edge.key()?,
+                    attributes: edge.local_attributes()?.into_iter().collect(),
+                });
+            }
+        }
+        
+        Ok(GraphData {
+            name: graph.name()?,
+            directed: graph.is_directed(),
+            strict: graph.is_strict(),
+            attributes: graph.graph_attributes()?.into_iter().collect(),
+            nodes,
+            edges,
+        })
+    }
+    
+    /// Creates a new graph from the snapshot.
+    ///
+    /// Edge endpoints that are not listed in `nodes` are created on demand.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Graph or an error
+    pub fn to_graph(&self) -> Result<Graph, GraphvizError> {
+        let graph = Graph::new_with_strictness(&self.name, self.directed, self.strict)?;
+        
+        for (name, value) in &self.attributes {
+            graph.set_attribute(name, value)?;
+        }
+        
+        for node_data in &self.nodes {
+            let node = graph.add_node(&node_data.name)?;
+            
+            for (name, value) in &node_data.attributes {
+                node.set_attribute(name, value)?;
+            }
+        }
+        
+        for edge_data in &self.edges {
+            let from = graph.add_node(&edge_data.from)?;
+            let to = graph.add_node(&edge_data.to)?;
+            let edge = graph.add_edge(&from, &to, edge_data.name.as_deref())?;
+            
+            for (name, value) in &edge_data.attributes {
+                edge.set_attribute(name, value)?;
+            }
+        }
+        
+        Ok(graph)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Graph {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GraphData::from_graph(self)
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Graph {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        GraphData::deserialize(deserializer)?
+            .to_graph()
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,192 @@
+//! Structural comparison of graphs.
+//!
+//! This module provides `GraphDiff`, the set of node, edge, and attribute
+//! changes between two graphs, as produced by `Graph::diff`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::data::{EdgeData, GraphData};
+use crate::error::GraphvizError;
+use crate::graph::Graph;
+
+/// The element of a graph whose attributes changed.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiffElement {
+    /// The graph itself
+    Graph,
+    /// A node, by name
+    Node(String),
+    /// An edge, by its endpoints, name, and position among its parallel edges
+    Edge {
+        /// Name of the source node
+        from: String,
+        /// Name of the target node
+        to: String,
+        /// Name of the edge, if it has one
+        name: Option<String>,
+        /// Position among edges with the same endpoints and name, in iteration order
+        index: usize,
+    },
+}
+
+/// A single attribute that differs between two graphs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeChange {
+    /// Name of the attribute
+    pub name: String,
+    /// Value in the original graph, if it was set
+    pub old: Option<String>,
+    /// Value in the new graph, if it is set
+    pub new: Option<String>,
+}
+
+/// The differences between two graphs.
+///
+/// Nodes are matched by name. Edges are matched by their endpoints and edge
+/// name; anonymous parallel edges between the same nodes are matched in
+/// iteration order. In undirected graphs the order of the endpoints does not
+/// matter. Each graph's edges are matched according to its own directedness,
+/// so when that differs, edges of the undirected graph only match directed
+/// edges whose endpoints are in name order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GraphDiff {
+    /// Whether each graph is directed, as (old, new), if the two differ
+    pub directed_changed: Option<(bool, bool)>,
+    /// Nodes present only in the new graph
+    pub added_nodes: Vec<String>,
+    /// Nodes present only in the original graph
+    pub removed_nodes: Vec<String>,
+    /// Edges present only in the new graph, as (source, target) names
+    pub added_edges: Vec<(String, String)>,
+    /// Edges present only in the original graph, as (source, target) names
+    pub removed_edges: Vec<(String, String)>,
+    /// Attribute changes on the graph and on matched nodes and edges
+    pub changed_attributes: BTreeMap<DiffElement, Vec<AttributeChange>>,
+}
+
+/// Key used to match edges between graphs.
+type EdgeKey = (String, String, Option<String>);
+
+impl GraphDiff {
+    /// Compares two graphs.
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - The original graph
+    /// * `new` - The graph to compare against it
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the differences or an error
+    pub fn between(old: &Graph, new: &Graph) -> Result<Self, GraphvizError> {
+        let old = GraphData::from_graph(old)?;
+        let new = GraphData::from_graph(new)?;
+        let mut diff = GraphDiff::default();
+        
+        if old.directed != new.directed {
+            diff.directed_changed = Some((old.directed, new.directed));
+        }
+        
+        diff.record_changes(DiffElement::Graph, &old.attributes, &new.attributes);
+        
+        let old_nodes: BTreeMap<_, _> = old.nodes.iter().map(|node| (&node.name, node)).collect();
+        let new_nodes: BTreeMap<_, _> = new.nodes.iter().map(|node| (&node.name, node)).collect();
+        
+        for (name, old_node) in &old_nodes {
+            match new_nodes.get(name) {
+                Some(new_node) => {
+                    let element = DiffElement::Node(name.to_string());
+                    diff.record_changes(element, &old_node.attributes, &new_node.attributes);
+                }
+                None => diff.removed_nodes.push(name.to_string()),
+            }
+        }
+        
+        diff.added_nodes = new_nodes.keys()
+            .filter(|name| !old_nodes.contains_key(*name))
+            .map(|name| name.to_string())
+            .collect();
+        
+        let old_edges = group_edges(&old.edges, old.directed);
+        let new_edges = group_edges(&new.edges, new.directed);
+        let keys: BTreeSet<_> = old_edges.keys().chain(new_edges.keys()).collect();
+        
+        for key in keys {
+            let old_group = old_edges.get(key).map_or(&[][..], Vec::as_slice);
+            let new_group = new_edges.get(key).map_or(&[][..], Vec::as_slice);
+            
+            for (index, (old_edge, new_edge)) in old_group.iter().zip(new_group).enumerate() {
+                let element = DiffElement::Edge {
+                    from: old_edge.from.clone(),
+                    to: old_edge.to.clone(),
+                    name: old_edge.name.clone(),
+                    index,
+                };
+                diff.record_changes(element, &old_edge.attributes, &new_edge.attributes);
+            }
+            
+            for edge in old_group.iter().skip(new_group.len()) {
+                diff.removed_edges.push((edge.from.clone(), edge.to.clone()));
+            }
+            
+            for edge in new_group.iter().skip(old_group.len()) {
+                diff.added_edges.push((edge.from.clone(), edge.to.clone()));
+            }
+        }
+        
+        Ok(diff)
+    }
+    
+    /// Checks whether the graphs compared equal.
+    ///
+    /// # Returns
+    ///
+    /// true if the directedness, nodes, edges, and attributes are all the same
+    pub fn is_empty(&self) -> bool {
+        self.directed_changed.is_none()
+            && self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_attributes.is_empty()
+    }
+    
+    /// Records the attributes that differ between two attribute maps.
+    fn record_changes(
+        &mut self,
+        element: DiffElement,
+        old: &BTreeMap<String, String>,
+        new: &BTreeMap<String, String>,
+    ) {
+        let names: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+        let changes: Vec<_> = names.into_iter()
+            .filter(|name| old.get(*name) != new.get(*name))
+            .map(|name| AttributeChange {
+                name: name.clone(),
+                old: old.get(name).cloned(),
+                new: new.get(name).cloned(),
+            })
+            .collect();
+        
+        if !changes.is_empty() {
+            self.changed_attributes.entry(element).or_default().extend(changes);
+        }
+    }
+}
+
+/// Groups edges by their matching key, preserving iteration order within each group.
+fn group_edges(edges: &[EdgeData], directed: bool) -> BTreeMap<EdgeKey, Vec<&EdgeData>> {
+    let mut groups: BTreeMap<EdgeKey, Vec<&EdgeData>> = BTreeMap::new();
+    
+    for edge in edges {
+        let (from, to) = if directed || edge.from <= edge.to {
+            (edge.from.clone(), edge.to.clone())
+        } else {
+            (edge.to.clone(), edge.from.clone())
+        };
+        
+        groups.entry((from, to, edge.name.clone())).or_default().push(edge);
+    }
+    
+    groups
+}
@@ -0,0 +1,319 @@
+//! Pure-Rust DOT text generation.
+//!
+//! This module provides `DotWriter`, an in-memory graph model that serializes to
+//! DOT text without calling into the GraphViz C library. It is useful when only a
+//! `.dot` file is needed, or as a fallback when the native GraphViz installation
+//! is unusable at runtime. Layout and rendering still require `Graph` and `Context`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+
+use crate::attr::quote_id;
+use crate::data::GraphData;
+use crate::error::GraphvizError;
+
+/// An in-memory graph that can be written out as DOT text.
+#[derive(Debug, Clone, Default)]
+pub struct DotWriter {
+    /// Name of the graph
+    name: String,
+    /// Whether the graph is directed
+    directed: bool,
+    /// Whether the graph is strict (no duplicate edges)
+    strict: bool,
+    /// Graph-level attributes in insertion order
+    attributes: Vec<(String, String)>,
+    /// Nodes in insertion order
+    nodes: Vec<DotNode>,
+    /// Index of each node in `nodes` by name
+    node_index: HashMap<String, usize>,
+    /// Edges in insertion order
+    edges: Vec<DotEdge>,
+}
+
+/// A node in a `DotWriter` graph.
+#[derive(Debug, Clone, Default)]
+pub struct DotNode {
+    /// Name of the node
+    name: String,
+    /// Attributes in insertion order
+    attributes: Vec<(String, String)>,
+}
+
+/// An edge in a `DotWriter` graph.
+#[derive(Debug, Clone, Default)]
+pub struct DotEdge {
+    /// Name of the source node
+    from: String,
+    /// Name of the target node
+    to: String,
+    /// Attributes in insertion order
+    attributes: Vec<(String, String)>,
+}
+
+// DotWriter implementation
+impl DotWriter {
+    /// Creates a new, empty DOT graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the graph
+    /// * `directed` - Whether the graph is directed or undirected
+    ///
+    /// # Returns
+    ///
+    /// A new DotWriter instance
+    pub fn new(name: &str, directed: bool) -> Self {
+        DotWriter {
+            name: name.to_owned(),
+            directed,
+            ..Default::default()
+        }
+    }
+    
+    /// Creates a DOT graph from a `GraphData` snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The snapshot to convert
+    ///
+    /// # Returns
+    ///
+    /// A new DotWriter instance
+    pub fn from_data(data: &GraphData) -> Self {
+        let mut writer = DotWriter::new(&data.name, data.directed).strict(data.strict);
+        
+        for (name, value) in &data.attributes {
+            writer.set_attribute(name, value);
+        }
+        
+        for node_data in &data.nodes {
+            let node = writer.add_node(&node_data.name);
+            
+            for (name, value) in &node_data.attributes {
+                node.attribute(name, value);
+            }
+        }
+        
+        for edge_data in &data.edges {
+            let edge = writer.add_edge(&edge_data.from, &edge_data.to);
+            
+            for (name, value) in &edge_data.attributes {
+                edge.attribute(name, value);
+            }
+        }
+        
+        writer
+    }
+    
+    /// Sets whether the graph is strict (no duplicate edges).
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether the graph is strict
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+    
+    /// Sets an attribute on the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    pub fn set_attribute(&mut self, name: &str, value: &str) -> &mut Self {
+        set_attribute(&mut self.attributes, name, value);
+        self
+    }
+    
+    /// Adds a node, or returns the existing node with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the node
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the node for setting attributes
+    pub fn add_node(&mut self, name: &str) -> &mut DotNode {
+        let index = match self.node_index.get(name) {
+            Some(&index) => index,
+            None => {
+                self.nodes.push(DotNode {
+                    name: name.to_owned(),
+                    attributes: Vec::new(),
+                });
+                self.node_index.insert(name.to_owned(), self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        };
+        
+        &mut self.nodes[index]
+    }
+    
+    /// Adds an edge between two nodes, creating missing nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The name of the source node
+    /// * `to` - The name of the target node
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the edge for setting attributes
+    pub fn add_edge(&mut self, from: &str, to: &str) -> &mut DotEdge {
+        self.add_node(from);
+        self.add_node(to);
+        
+        self.edges.push(DotEdge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            attributes: Vec::new(),
+        });
+        
+        self.edges.last_mut().expect("edge was just pushed")
+    }
+    
+    /// Gets the number of nodes in the graph.
+    ///
+    /// # Returns
+    ///
+    /// The number of nodes in the graph
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+    
+    /// Gets the number of edges in the graph.
+    ///
+    /// # Returns
+    ///
+    /// The number of edges in the graph
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+    
+    /// Formats the graph as DOT text.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the DOT text, or `GraphvizError::UnquotableId` if
+    /// a name or attribute cannot be written as a DOT identifier
+    pub fn to_dot(&self) -> Result<String, GraphvizError> {
+        let strict = if self.strict { "strict " } else { "" };
+        let kind = if self.directed { "digraph" } else { "graph" };
+        let edge_op = if self.directed { "->" } else { "--" };
+        
+        let mut dot = format!("{}{} {} {{\n", strict, kind, quote_id(&self.name)?);
+        
+        if !self.attributes.is_empty() {
+            dot.push_str(&format!("    graph{};\n", attribute_list(&self.attributes)?));
+        }
+        
+        for node in &self.nodes {
+            dot.push_str(&format!("    {}{};\n", quote_id(&node.name)?, attribute_list(&node.attributes)?));
+        }
+        
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "    {} {} {}{};\n",
+                quote_id(&edge.from)?,
+                edge_op,
+                quote_id(&edge.to)?,
+                attribute_list(&edge.attributes)?,
+            ));
+        }
+        
+        dot.push('}');
+        Ok(dot)
+    }
+    
+    /// Writes the graph as DOT text to a writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to write to
+    ///
+    /// # Returns
+    ///
+    /// A Result indicating success or failure, including
+    /// `GraphvizError::UnquotableId` as described for `to_dot`
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), GraphvizError> {
+        writer.write_all(self.to_dot()?.as_bytes())?;
+        Ok(())
+    }
+}
+
+// DotNode implementation
+impl DotNode {
+    /// Sets an attribute on the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    pub fn attribute(&mut self, name: &str, value: &str) -> &mut Self {
+        set_attribute(&mut self.attributes, name, value);
+        self
+    }
+}
+
+// DotEdge implementation
+impl DotEdge {
+    /// Sets an attribute on the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining
+    pub fn attribute(&mut self, name: &str, value: &str) -> &mut Self {
+        set_attribute(&mut self.attributes, name, value);
+        self
+    }
+}
+
+// Formatting fails with `fmt::Error` where `to_dot` would return an error
+impl fmt::Display for DotWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_dot().map_err(|_| fmt::Error)?)
+    }
+}
+
+/// Inserts or replaces an attribute, keeping insertion order.
+fn set_attribute(attributes: &mut Vec<(String, String)>, name: &str, value: &str) {
+    match attributes.iter_mut().find(|(existing, _)| existing == name) {
+        Some(entry) => entry.1 = value.to_owned(),
+        None => attributes.push((name.to_owned(), value.to_owned())),
+    }
+}
+
+/// Formats an attribute list such as ` [shape="box", color="red"]`, or nothing if empty.
+fn attribute_list(attributes: &[(String, String)]) -> Result<String, GraphvizError> {
+    if attributes.is_empty() {
+        return Ok(String::new());
+    }
+    
+    let items = attributes
+        .iter()
+        .map(|(name, value)| Ok(format!("{}={}", quote_id(name)?, quote_id(value)?)))
+        .collect::<Result<Vec<String>, GraphvizError>>()?;
+    
+    Ok(format!(" [{}]", items.join(", ")))
+}
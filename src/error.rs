@@ -5,25 +5,51 @@
 
 use std::error::Error;
 use std::fmt;
-use std::ffi::NulError;
+use std::ffi::{CString, NulError};
 
 /// Enumeration of all possible errors that can occur during GraphViz operations.
 #[derive(Debug)]
 pub enum GraphvizError {
     /// Error creating a graph structure
     GraphCreationFailed,
+    /// Input could not be parsed as a DOT graph
+    ParseFailed,
     /// Error creating a node structure
     NodeCreationFailed,
     /// Error creating an edge structure
     EdgeCreationFailed,
+    /// A node passed to a graph belongs to a different root graph (node name)
+    ForeignNode(String),
+    /// Another node already has the requested name
+    DuplicateName(String),
+    /// A strict graph already has an edge between the two nodes (source and target names)
+    DuplicateEdge(String, String),
+    /// An edge in a batch passed to `Graph::add_edges` could not be created
+    EdgeBatchFailed {
+        /// Position of the failing edge in the batch
+        index: usize,
+        /// Name of the edge's source node
+        from: String,
+        /// Name of the edge's target node
+        to: String,
+        /// The underlying error
+        source: Box<GraphvizError>,
+    },
     /// Error during layout computation
     LayoutFailed,
     /// Error during rendering process
     RenderFailed,
     /// Invalid string for C FFI (contains null bytes)
-    InvalidString,
+    InvalidString {
+        /// The argument that contained the null byte, e.g. "node name"
+        field: &'static str,
+    },
+    /// String cannot be written as a quoted DOT identifier (the string)
+    UnquotableId(String),
     /// Error setting an attribute
     AttributeSetFailed,
+    /// Attribute name rejected by a builder in strict mode
+    UnknownAttribute(String),
     /// Error getting an attribute
     AttributeGetFailed,
     /// Error freeing layout resources
@@ -36,39 +62,76 @@ pub enum GraphvizError {
     ContextCreationFailed,
     /// Invalid format specified
     InvalidFormat,
+    /// No installed plugin can render the requested format (format name)
+    UnsupportedFormat(String),
     /// Invalid engine specified
     InvalidEngine,
     /// Failed to initialize GraphViz
     InitializationFailed,
+    /// The linked GraphViz library does not match the ABI the bindings expect
+    VersionMismatch {
+        /// The version reported by the linked library
+        detected: String,
+        /// The versions the bindings support
+        expected: String,
+    },
     /// Failed to clean up GraphViz resources
     CleanupFailed,
     /// System error (with errno)
     SystemError(i32),
     /// File I/O error
     IoError(std::io::Error),
+    /// Rendered output is not valid JSON
+    InvalidJson,
+    /// Edge weight is negative or not a number
+    InvalidWeight,
+    /// The operation requires an acyclic graph, but the graph contains a cycle
+    CyclicGraph,
+    /// Layout did not finish within the allotted time
+    LayoutTimeout,
+    /// Rendered output is too large to address (length in bytes)
+    RenderTooLarge(usize),
 }
 
 impl fmt::Display for GraphvizError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             GraphvizError::GraphCreationFailed => write!(f, "Failed to create graph"),
+            GraphvizError::ParseFailed => write!(f, "Failed to parse DOT input"),
             GraphvizError::NodeCreationFailed => write!(f, "Failed to create node"),
             GraphvizError::EdgeCreationFailed => write!(f, "Failed to create edge"),
+            GraphvizError::ForeignNode(name) => write!(f, "Node {} belongs to a different graph", name),
+            GraphvizError::DuplicateName(name) => write!(f, "A node named {} already exists", name),
+            GraphvizError::DuplicateEdge(from, to) => write!(f, "An edge from {} to {} already exists", from, to),
+            GraphvizError::EdgeBatchFailed { index, from, to, source } => {
+                write!(f, "Failed to create edge {} ({} -> {}): {}", index, from, to, source)
+            }
             GraphvizError::LayoutFailed => write!(f, "Failed to compute layout"),
             GraphvizError::RenderFailed => write!(f, "Failed to render graph"),
-            GraphvizError::InvalidString => write!(f, "String contains null bytes"),
+            GraphvizError::InvalidString { field } => write!(f, "{} contains null byte", field),
+            GraphvizError::UnquotableId(id) => write!(f, "{:?} cannot be written as a DOT identifier", id),
             GraphvizError::AttributeSetFailed => write!(f, "Failed to set attribute"),
+            GraphvizError::UnknownAttribute(name) => write!(f, "Unknown attribute: {}", name),
             GraphvizError::AttributeGetFailed => write!(f, "Failed to get attribute"),
             GraphvizError::FreeLayoutFailed => write!(f, "Failed to free layout resources"),
             GraphvizError::InvalidUtf8 => write!(f, "String is not valid UTF-8"),
             GraphvizError::NullPointer(context) => write!(f, "Null pointer encountered: {}", context),
             GraphvizError::ContextCreationFailed => write!(f, "Failed to create GraphViz context"),
             GraphvizError::InvalidFormat => write!(f, "Invalid output format specified"),
+            GraphvizError::UnsupportedFormat(format) => write!(f, "Output format '{}' is not supported by the installed plugins", format),
             GraphvizError::InvalidEngine => write!(f, "Invalid layout engine specified"),
             GraphvizError::InitializationFailed => write!(f, "Failed to initialize GraphViz"),
+            GraphvizError::VersionMismatch { detected, expected } => {
+                write!(f, "Linked GraphViz version {} is incompatible (expected {})", detected, expected)
+            }
             GraphvizError::CleanupFailed => write!(f, "Failed to clean up GraphViz resources"),
             GraphvizError::SystemError(errno) => write!(f, "System error occurred (errno: {})", errno),
             GraphvizError::IoError(err) => write!(f, "I/O error: {}", err),
+            GraphvizError::InvalidJson => write!(f, "Rendered output is not valid JSON"),
+            GraphvizError::InvalidWeight => write!(f, "Edge weight must be a non-negative number"),
+            GraphvizError::CyclicGraph => write!(f, "Graph contains a cycle"),
+            GraphvizError::LayoutTimeout => write!(f, "Layout did not finish within the timeout"),
+            GraphvizError::RenderTooLarge(length) => write!(f, "Rendered output is too large ({} bytes)", length),
         }
     }
 }
@@ -77,6 +140,7 @@ impl Error for GraphvizError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             GraphvizError::IoError(err) => Some(err),
+            GraphvizError::EdgeBatchFailed { source, .. } => Some(source.as_ref()),
             _ => None,
         }
     }
@@ -84,10 +148,24 @@ impl Error for GraphvizError {
 
 impl From<NulError> for GraphvizError {
     fn from(_: NulError) -> Self {
-        GraphvizError::InvalidString
+        GraphvizError::InvalidString { field: "string" }
     }
 }
 
+/// Converts a string argument to a C string, naming the argument if it contains a null byte.
+///
+/// # Arguments
+///
+/// * `value` - The string to convert
+/// * `field` - Description of the argument, e.g. "node name"
+///
+/// # Returns
+///
+/// A Result containing the C string or `GraphvizError::InvalidString`
+pub(crate) fn c_string<T: Into<Vec<u8>>>(value: T, field: &'static str) -> Result<CString, GraphvizError> {
+    CString::new(value).map_err(|_| GraphvizError::InvalidString { field })
+}
+
 impl From<std::io::Error> for GraphvizError {
     fn from(err: std::io::Error) -> Self {
         GraphvizError::IoError(err)
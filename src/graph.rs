@@ -3,15 +3,27 @@
 //! This module provides Rust-idiomatic interfaces for creating and manipulating
 //! graphs, nodes, and edges while ensuring safe memory management.
 
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::ffi::{CStr, CString};
+use std::fmt;
+use std::io::{self, Read};
+use std::os::raw::{c_char, c_int, c_void};
 use std::marker::PhantomData;
+use std::ops::Deref;
+use std::path::Path;
 use std::ptr;
+use std::slice;
+use std::sync::{Arc, OnceLock};
 use std::iter::Iterator;
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
 use graphviz_sys as sys;
-use crate::error::GraphvizError;
-use crate::attr::AttributeContainer;
+use crate::error::{self, GraphvizError};
+use crate::attr::{self, AttributeContainer, LabelLoc, LabelJust};
+use crate::diff::GraphDiff;
+use crate::layout::{self, ContextHandle, NodeGeometry};
 
 /// A GraphViz graph structure with RAII-based memory management.
 pub struct Graph {
@@ -19,6 +31,8 @@ pub struct Graph {
     pub(crate) inner: *mut sys::Agraph_t,
     /// Indicates if this Graph owns the inner pointer and should free it on drop
     owned: bool,
+    /// The context holding this graph's active layout, if any
+    pub(crate) layout_context: Option<Arc<ContextHandle>>,
 }
 
 /// A node within a GraphViz graph.
@@ -71,6 +85,27 @@ pub struct NodeBuilder<'a> {
     name: String,
     /// Attributes to set on the node
     attributes: HashMap<String, String>,
+    /// Extra accepted attribute names; Some enables strict attribute checking
+    allowed_attributes: Option<HashSet<String>>,
+}
+
+/// A builder for polygon-shaped nodes, created by `NodeBuilder::polygon`.
+pub struct PolygonBuilder<'a> {
+    /// The node builder receiving the polygon attributes
+    node: NodeBuilder<'a>,
+}
+
+/// A group of nodes to align, created by `Graph::align_group`.
+///
+/// Nodes are collected with `add`, then `same_rank` places them all on one
+/// rank. In a top-to-bottom dot layout that lines them up horizontally.
+pub struct AlignmentGroup<'a> {
+    /// Reference to the parent graph
+    graph: &'a Graph,
+    /// The nodes to align, in the order they were added
+    nodes: Vec<*mut sys::Agnode_t>,
+    /// Whether the nodes are kept in the order they were added
+    ordered: bool,
 }
 
 /// A builder for creating edges with attributes.
@@ -85,6 +120,25 @@ pub struct EdgeBuilder<'a> {
     name: Option<String>,
     /// Attributes to set on the edge
     attributes: HashMap<String, String>,
+    /// Extra accepted attribute names; Some enables strict attribute checking
+    allowed_attributes: Option<HashSet<String>>,
+}
+
+/// A reusable set of node attributes for creating many similar nodes.
+///
+/// Unlike `NodeBuilder`, a template is not consumed when used, so one
+/// template can create any number of nodes in any number of graphs.
+#[derive(Debug, Clone, Default)]
+pub struct NodeTemplate {
+    /// Attributes to set on each created node
+    attributes: HashMap<String, String>,
+}
+
+/// A reusable set of edge attributes for creating many similar edges.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeTemplate {
+    /// Attributes to set on each created edge
+    attributes: HashMap<String, String>,
 }
 
 /// A builder for creating graphs with attributes.
@@ -99,10 +153,115 @@ pub struct GraphBuilder {
     attributes: HashMap<String, String>,
 }
 
+/// A subgraph or cluster within a GraphViz graph.
+///
+/// A subgraph dereferences to `Graph`, so nodes and edges added through it
+/// belong to both the subgraph and its root graph. Subgraphs whose name starts
+/// with `cluster` are drawn as boxed clusters by dot.
+///
+/// The lifetime parameter 'a ensures that the Subgraph cannot outlive its parent Graph.
+pub struct Subgraph<'a> {
+    /// The subgraph as an unowned graph handle
+    graph: Graph,
+    /// Phantom data to tie the Subgraph's lifetime to the Graph
+    _phantom: PhantomData<&'a Graph>,
+}
+
+/// A borrowed handle to the graph a node belongs to.
+///
+/// A GraphRef dereferences to `Graph` but can never be turned into an owned
+/// `Graph`, so dropping it never closes the underlying graph.
+///
+/// The lifetime parameter 'a ensures that the GraphRef cannot outlive the graph it refers to.
+pub struct GraphRef<'a> {
+    /// The graph as an unowned graph handle
+    graph: Graph,
+    /// Phantom data to tie the GraphRef's lifetime to the Graph
+    _phantom: PhantomData<&'a Graph>,
+}
+
+/// Iterator over the direct subgraphs of a graph.
+pub struct SubgraphIter<'a> {
+    /// Pointer to the next subgraph in the iteration sequence
+    next: *mut sys::Agraph_t,
+    /// Phantom data to tie the iterator's lifetime to the Graph
+    _phantom: PhantomData<&'a Graph>,
+}
+
+/// A problem found by `Graph::validate` before layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// The graph has no nodes
+    EmptyGraph,
+    /// An attribute value that GraphViz will reject or ignore
+    InvalidAttribute {
+        /// The graph element carrying the attribute, e.g. `node "a"`
+        target: String,
+        /// Name of the attribute
+        name: String,
+        /// The rejected value
+        value: String,
+    },
+}
+
+/// The kind of a graph, corresponding to the GraphViz graph descriptors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphKind {
+    /// A directed graph (`Agdirected`)
+    Directed,
+    /// A directed graph without parallel edges (`Agstrictdirected`)
+    StrictDirected,
+    /// An undirected graph (`Agundirected`)
+    Undirected,
+    /// An undirected graph without parallel edges (`Agstrictundirected`)
+    StrictUndirected,
+}
+
+/// A snapshot of a graph's structural characteristics, as produced by `Graph::stats`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphStats {
+    /// Number of nodes
+    pub node_count: usize,
+    /// Number of edges
+    pub edge_count: usize,
+    /// Whether the graph is directed
+    pub directed: bool,
+    /// Whether the graph is strict
+    pub strict: bool,
+    /// Edge density, as computed by `Graph::density`
+    pub density: f64,
+    /// Number of connected components, ignoring edge direction
+    pub connected_components: usize,
+    /// Whether the graph has no cycles (self-loops count as cycles)
+    pub acyclic: bool,
+}
+
+/// Nodes and (tail, head) edge index pairs, as produced by `Graph::indexed_edges`.
+type IndexedEdges<'a> = (Vec<Node<'a>>, Vec<(usize, usize)>);
+
+/// Attributes whose values must be non-negative numbers.
+const NON_NEGATIVE_ATTRIBUTES: &[&str] = &[
+    "arrowsize",
+    "fontsize",
+    "height",
+    "labelfontsize",
+    "penwidth",
+    "peripheries",
+    "weight",
+    "width",
+];
+
 // Graph implementation
 impl Graph {
     /// Creates a new GraphViz graph with the specified name and direction.
     ///
+    /// Every graph created here gets its own string pool and attribute
+    /// dictionaries, so several graphs may share a name without sharing
+    /// attributes or defaults. The name only appears in DOT output. The one
+    /// piece of state GraphViz shares between graphs, the prototype graph
+    /// whose attribute declarations are copied into every new graph, is never
+    /// modified by this crate.
+    ///
     /// # Arguments
     ///
     /// * `name` - The name of the graph
@@ -112,7 +271,7 @@ impl Graph {
     ///
     /// A Result containing the new Graph or an error
     pub fn new(name: &str, directed: bool) -> Result<Self, GraphvizError> {
-        let name = CString::new(name)?;
+        let name = error::c_string(name, "graph name")?;
         let desc = if directed { 
             unsafe { sys::Agdirected } 
         } else { 
@@ -127,7 +286,7 @@ impl Graph {
             return Err(GraphvizError::GraphCreationFailed);
         }
         
-        Ok(Graph { inner, owned: true })
+        Ok(Graph { inner, owned: true, layout_context: None })
     }
     
     /// Creates a new GraphViz graph with the specified name, direction, and strictness.
@@ -144,7 +303,7 @@ impl Graph {
     ///
     /// A Result containing the new Graph or an error
     pub fn new_with_strictness(name: &str, directed: bool, strict: bool) -> Result<Self, GraphvizError> {
-        let name = CString::new(name)?;
+        let name = error::c_string(name, "graph name")?;
         let desc = match (directed, strict) {
             (true, true) => unsafe { sys::Agstrictdirected },
             (true, false) => unsafe { sys::Agdirected },
@@ -160,7 +319,93 @@ impl Graph {
             return Err(GraphvizError::GraphCreationFailed);
         }
         
-        Ok(Graph { inner, owned: true })
+        Ok(Graph { inner, owned: true, layout_context: None })
+    }
+    
+    /// Parses a graph from DOT text supplied by a reader.
+    ///
+    /// The input is fed to GraphViz's parser in small chunks as it asks for
+    /// more, so memory use is bounded by the size of the resulting graph
+    /// rather than the size of the input text. Only the first graph in the
+    /// input is read. Wrap unbuffered sources such as `File` in a `BufReader`
+    /// to avoid many small reads.
+    ///
+    /// GraphViz's DOT parser keeps process-global state, so parsing holds the
+    /// crate-wide GraphViz lock and waits for any layout or render in progress.
+    /// The reader runs while the lock is held and must not call back into
+    /// vizoxide layout or rendering functions, or it will deadlock.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of DOT text
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the parsed Graph, `GraphvizError::IoError` if the
+    /// reader failed, or `GraphvizError::ParseFailed` if the text is not valid DOT
+    pub fn from_dot_reader<R: Read>(mut reader: R) -> Result<Self, GraphvizError> {
+        let mut channel = ReadChannel { reader: &mut reader, error: None };
+        // The graph keeps the I/O discipline pointer, so it must outlive every graph
+        let io_disc = READ_DISC.get_or_init(|| sys::Agiodisc_t {
+            afread: Some(read_channel),
+            ..unsafe { sys::AgIoDisc }
+        });
+        let mut disc = sys::Agdisc_t {
+            id: &raw mut sys::AgIdDisc,
+            io: io_disc as *const sys::Agiodisc_t as *mut _,
+        };
+        
+        let inner = layout::serialized(|| unsafe {
+            sys::agread(&mut channel as *mut ReadChannel as *mut c_void, &mut disc)
+        });
+        
+        if let Some(err) = channel.error {
+            if !inner.is_null() {
+                unsafe { sys::agclose(inner) };
+            }
+            return Err(GraphvizError::IoError(err));
+        }
+        
+        if inner.is_null() {
+            return Err(GraphvizError::ParseFailed);
+        }
+        
+        Ok(Graph { inner, owned: true, layout_context: None })
+    }
+    
+    /// Takes ownership of a graph created through `graphviz_sys`.
+    ///
+    /// The returned Graph closes the graph with `agclose` when dropped.
+    ///
+    /// # Safety
+    ///
+    /// `inner` must be a non-null pointer to a root graph (not a subgraph),
+    /// for example from `agopen` or `agread`, that is still open. Nothing else
+    /// may close it or wrap it with `from_raw` again, and it must not have a
+    /// layout applied that is not freed before the graph is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The graph pointer to take ownership of
+    ///
+    /// # Returns
+    ///
+    /// A Graph owning the pointer
+    pub unsafe fn from_raw(inner: *mut sys::Agraph_t) -> Self {
+        Graph { inner, owned: true, layout_context: None }
+    }
+    
+    /// Gets the underlying `Agraph_t` pointer for use with `graphviz_sys`.
+    ///
+    /// The pointer stays owned by this Graph and is valid until it is
+    /// dropped. Closing it, or freeing anything it owns, through the raw
+    /// pointer leads to a double free.
+    ///
+    /// # Returns
+    ///
+    /// The raw graph pointer
+    pub fn as_raw(&self) -> *mut sys::Agraph_t {
+        self.inner
     }
     
     /// Creates a new builder for configuring and creating a graph.
@@ -176,6 +421,38 @@ impl Graph {
         GraphBuilder::new(name)
     }
 
+    /// Builds a graph from a list of edges given as pairs of node names.
+    ///
+    /// Nodes are created the first time they are named, and repeated pairs
+    /// produce a single edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the graph
+    /// * `directed` - Whether the graph is directed
+    /// * `edges` - The (source, target) node name pairs
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Graph or an error
+    pub fn from_edges<'e, I>(name: &str, directed: bool, edges: I) -> Result<Self, GraphvizError>
+    where
+        I: IntoIterator<Item = (&'e str, &'e str)>,
+    {
+        let graph = Graph::new(name, directed)?;
+        
+        for (from, to) in edges {
+            let from = graph.add_node(from)?;
+            let to = graph.add_node(to)?;
+            
+            if graph.find_edge(&from, &to).is_none() {
+                graph.add_edge(&from, &to, None)?;
+            }
+        }
+        
+        Ok(graph)
+    }
+    
     /// Adds a node to the graph with the specified name.
     ///
     /// # Arguments
@@ -186,7 +463,7 @@ impl Graph {
     ///
     /// A Result containing the new Node or an error
     pub fn add_node(&self, name: &str) -> Result<Node<'_>, GraphvizError> {
-        let name = CString::new(name)?;
+        let name = error::c_string(name, "node name")?;
         let inner = unsafe { 
             sys::agnode(self.inner, name.as_ptr() as *mut _, 1) 
         };
@@ -221,9 +498,13 @@ impl Graph {
     ///
     /// # Returns
     ///
-    /// A Result containing the new Edge or an error
+    /// A Result containing the new Edge or an error, including
+    /// `GraphvizError::ForeignNode` if either node belongs to another graph
     pub fn add_edge(&self, from: &Node, to: &Node, name: Option<&str>) -> Result<Edge<'_>, GraphvizError> {
-        let name_cstr = name.map(CString::new).transpose()?;
+        self.check_same_root(from)?;
+        self.check_same_root(to)?;
+        
+        let name_cstr = name.map(|name| error::c_string(name, "edge name")).transpose()?;
         let name_ptr = name_cstr.as_ref()
             .map_or(ptr::null_mut(), |cs| cs.as_ptr() as *mut _);
         
@@ -238,6 +519,76 @@ impl Graph {
         Ok(Edge { inner, _phantom: PhantomData })
     }
     
+    /// Adds an edge between two nodes given by name, creating missing nodes.
+    ///
+    /// Nodes are looked up or created exactly as by `add_node`.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The name of the source node
+    /// * `to` - The name of the target node
+    /// * `name` - Optional name for the edge
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Edge or an error
+    pub fn add_edge_by_name(&self, from: &str, to: &str, name: Option<&str>) -> Result<Edge<'_>, GraphvizError> {
+        let from = self.add_node(from)?;
+        let to = self.add_node(to)?;
+        self.add_edge(&from, &to, name)
+    }
+    
+    /// Adds many edges at once, each with its own attributes.
+    ///
+    /// Each item is a (source, target, attributes) triple. Endpoints are looked
+    /// up or created by name as by `add_node`, and every attribute is set on
+    /// the new edge. Creation stops at the first failure; edges added before it
+    /// remain in the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `edges` - The (source, target, attributes) triples
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new edges in input order, or
+    /// `GraphvizError::EdgeBatchFailed` identifying the failing edge
+    pub fn add_edges<I, S, A, K, V>(&self, edges: I) -> Result<Vec<Edge<'_>>, GraphvizError>
+    where
+        I: IntoIterator<Item = (S, S, A)>,
+        S: AsRef<str>,
+        A: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut created = Vec::new();
+        
+        for (index, (from, to, attributes)) in edges.into_iter().enumerate() {
+            let (from, to) = (from.as_ref(), to.as_ref());
+            let result = self.add_edge_by_name(from, to, None).and_then(|edge| {
+                for (name, value) in attributes {
+                    edge.set_attribute(name.as_ref(), value.as_ref())?;
+                }
+                
+                Ok(edge)
+            });
+            
+            match result {
+                Ok(edge) => created.push(edge),
+                Err(source) => {
+                    return Err(GraphvizError::EdgeBatchFailed {
+                        index,
+                        from: from.to_owned(),
+                        to: to.to_owned(),
+                        source: Box::new(source),
+                    });
+                }
+            }
+        }
+        
+        Ok(created)
+    }
+    
     /// Creates a builder for configuring and adding an edge.
     ///
     /// # Arguments
@@ -253,6 +604,15 @@ impl Graph {
         EdgeBuilder::new(self, from, to, name)
     }
     
+    /// Starts a group of nodes to align on the same rank.
+    ///
+    /// # Returns
+    ///
+    /// An AlignmentGroup for adding the nodes
+    pub fn align_group(&self) -> AlignmentGroup<'_> {
+        AlignmentGroup::new(self)
+    }
+    
     /// Gets a node by name, returning None if the node doesn't exist.
     ///
     /// # Arguments
@@ -263,7 +623,7 @@ impl Graph {
     ///
     /// Option containing the node if found
     pub fn get_node(&self, name: &str) -> Result<Option<Node<'_>>, GraphvizError> {
-        let name = CString::new(name)?;
+        let name = error::c_string(name, "node name")?;
         let inner = unsafe { 
             sys::agnode(self.inner, name.as_ptr() as *mut _, 0) 
         };
@@ -297,6 +657,31 @@ impl Graph {
         }
     }
     
+    /// Gets every edge from one node to another, including parallel edges.
+    ///
+    /// In undirected graphs, edges stored in the opposite direction connect
+    /// the same pair of nodes and are included as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The source node
+    /// * `to` - The target node
+    ///
+    /// # Returns
+    ///
+    /// The edges between the nodes
+    pub fn edges_between<'a>(&'a self, from: &'a Node, to: &'a Node) -> Vec<Edge<'a>> {
+        let mut edges: Vec<Edge<'a>> = self.out_edges(from)
+            .filter(|edge| edge.head().inner == to.inner)
+            .collect();
+        
+        if !self.is_directed() && from.inner != to.inner {
+            edges.extend(self.out_edges(to).filter(|edge| edge.head().inner == from.inner));
+        }
+        
+        edges
+    }
+    
     /// Creates an iterator over all nodes in the graph.
     ///
     /// # Returns
@@ -309,6 +694,62 @@ impl Graph {
         }
     }
     
+    /// Creates an iterator over the direct subgraphs of the graph.
+    ///
+    /// Only immediate children are visited; call `subgraphs` on each yielded
+    /// subgraph to walk nested subgraphs recursively.
+    ///
+    /// # Returns
+    ///
+    /// A SubgraphIter that iterates over the direct subgraphs
+    pub fn subgraphs(&self) -> SubgraphIter<'_> {
+        SubgraphIter {
+            next: unsafe { sys::agfstsubg(self.inner) },
+            _phantom: PhantomData,
+        }
+    }
+    
+    /// Finds the innermost cluster containing a node.
+    ///
+    /// Subgraphs that are not clusters are searched through but never
+    /// returned. If the node belongs to several sibling clusters, the first
+    /// one in iteration order wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to look up
+    ///
+    /// # Returns
+    ///
+    /// The innermost cluster containing the node, or None
+    pub fn cluster_of(&self, node: &Node) -> Option<Subgraph<'_>> {
+        innermost_subgraph(self.inner, node.inner, true).map(Subgraph::from_raw)
+    }
+    
+    /// Adds a subgraph with the specified name, or gets it if it already exists.
+    ///
+    /// Name the subgraph `cluster...` to have dot draw it as a cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the subgraph
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the Subgraph or an error
+    pub fn add_subgraph(&self, name: &str) -> Result<Subgraph<'_>, GraphvizError> {
+        let name = error::c_string(name, "subgraph name")?;
+        let inner = unsafe { 
+            sys::agsubg(self.inner, name.as_ptr() as *mut _, 1) 
+        };
+        
+        if inner.is_null() {
+            return Err(GraphvizError::GraphCreationFailed);
+        }
+        
+        Ok(Subgraph::from_raw(inner))
+    }
+    
     /// Creates an iterator over all edges in the graph.
     ///
     /// # Returns
@@ -369,342 +810,2503 @@ impl Graph {
         }
     }
     
-    /// Gets the number of nodes in the graph.
+    /// Creates an iterator over a node's outgoing edges paired with their target nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to get neighbors of
     ///
     /// # Returns
     ///
-    /// The number of nodes in the graph
-    pub fn node_count(&self) -> i32 {
-        unsafe { sys::agnnodes(self.inner) }
+    /// An iterator yielding each (target node, edge) pair
+    pub fn adjacent<'a>(&'a self, node: &'a Node) -> impl Iterator<Item = (Node<'a>, Edge<'a>)> {
+        self.out_edges(node).map(|edge| (edge.head(), edge))
     }
     
-    /// Gets the number of edges in the graph.
+    /// Gets the names of all nodes in the graph.
     ///
     /// # Returns
     ///
-    /// The number of edges in the graph
-    pub fn edge_count(&self) -> i32 {
-        unsafe { sys::agnedges(self.inner) }
+    /// A Result containing the node names in iteration order, or
+    /// `GraphvizError::InvalidUtf8` if a name is not valid UTF-8
+    pub fn node_names(&self) -> Result<Vec<String>, GraphvizError> {
+        self.nodes().map(|node| node.name()).collect()
     }
     
-    /// Sets an attribute on the graph.
-    ///
-    /// # Arguments
-    ///
-    /// * `name` - The attribute name
-    /// * `value` - The attribute value
+    /// Gets the source and target node names of every edge in the graph.
     ///
     /// # Returns
     ///
-    /// Result indicating success or failure
-    pub fn set_attribute(&self, name: &str, value: &str) -> Result<(), GraphvizError> {
-        let name = CString::new(name)?;
-        let value = CString::new(value)?;
+    /// A Result containing `(from, to)` name pairs, or `GraphvizError::InvalidUtf8`
+    /// if a name is not valid UTF-8
+    pub fn edge_endpoints(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        let mut endpoints = Vec::new();
         
-        let sym = unsafe {
-            sys::agattr(
-                self.inner,
-                sys::AGRAPH as i32,
-                name.as_ptr() as *mut _,
-                value.as_ptr() as *mut _,
-            )
-        };
-        
-        if sym.is_null() {
-            return Err(GraphvizError::AttributeSetFailed);
+        for node in self.nodes() {
+            for edge in self.out_edges(&node) {
+                endpoints.push((edge.tail().name()?, edge.head().name()?));
+            }
         }
         
-        let result = unsafe { 
-            sys::agxset(self.inner as *mut _, sym, value.as_ptr() as *mut _) 
-        };
-        
-        if result == 0 {
-            Ok(())
-        } else {
-            Err(GraphvizError::AttributeSetFailed)
-        }
+        Ok(endpoints)
     }
     
-    /// Gets an attribute value from the graph.
+    /// Calls a closure on every node in the graph.
+    ///
+    /// Iteration stops at the first error returned by the closure.
     ///
     /// # Arguments
     ///
-    /// * `name` - The attribute name
+    /// * `f` - The closure to call, typically to read or set attributes
     ///
     /// # Returns
     ///
-    /// Option containing the attribute value if it exists
-    pub fn get_attribute(&self, name: &str) -> Result<Option<String>, GraphvizError> {
-        let name = CString::new(name)?;
-        
-        let value = unsafe { 
-            sys::agget(self.inner as *mut _, name.as_ptr() as *mut _) 
-        };
-        
-        if value.is_null() {
-            return Ok(None);
+    /// Result indicating success or the first error
+    pub fn for_each_node<F: FnMut(&Node) -> Result<(), GraphvizError>>(&self, mut f: F) -> Result<(), GraphvizError> {
+        for node in self.nodes() {
+            f(&node)?;
         }
         
-        let c_str = unsafe { CStr::from_ptr(value) };
-        let value_str = c_str.to_str()
-            .map_err(|_| GraphvizError::InvalidUtf8)?
-            .to_owned();
-        
-        Ok(Some(value_str))
+        Ok(())
     }
     
-    /// Removes a node from the graph.
+    /// Calls a closure on every edge in the graph, visiting each edge once.
+    ///
+    /// Iteration stops at the first error returned by the closure.
     ///
     /// # Arguments
     ///
-    /// * `node` - The node to remove
+    /// * `f` - The closure to call, typically to read or set attributes
     ///
     /// # Returns
     ///
-    /// Result indicating success or failure
-    pub fn remove_node(&self, node: Node) -> Result<(), GraphvizError> {
-        let result = unsafe { 
-            sys::agdelnode(self.inner, node.inner) 
-        };
-        
-        if result == 0 {
-            Ok(())
-        } else {
-            Err(GraphvizError::NodeCreationFailed)
+    /// Result indicating success or the first error
+    pub fn for_each_edge<F: FnMut(&Edge) -> Result<(), GraphvizError>>(&self, mut f: F) -> Result<(), GraphvizError> {
+        for node in self.nodes() {
+            for edge in self.out_edges(&node) {
+                f(&edge)?;
+            }
         }
+        
+        Ok(())
     }
     
-    /// Removes an edge from the graph.
-    ///
-    /// # Arguments
+    /// Builds the adjacency matrix of the graph.
     ///
-    /// * `edge` - The edge to remove
+    /// Rows and columns follow the order of `nodes()`, which is the order the
+    /// nodes were created in and stays the same until nodes are added or
+    /// removed. Entry `[i][j]` counts the edges from node `i` to node `j`, so
+    /// parallel edges count separately. Undirected graphs give a symmetric
+    /// matrix, with each self-loop counted once on the diagonal.
     ///
     /// # Returns
     ///
-    /// Result indicating success or failure
-    pub fn remove_edge(&self, edge: Edge) -> Result<(), GraphvizError> {
-        let result = unsafe { 
-            sys::agdeledge(self.inner, edge.inner) 
-        };
+    /// A Result containing the node names in row order and the matrix, or an error
+    pub fn adjacency_matrix(&self) -> Result<(Vec<String>, Vec<Vec<u32>>), GraphvizError> {
+        let (nodes, edges) = self.indexed_edges()?;
+        let names = nodes.iter().map(Node::name).collect::<Result<Vec<_>, _>>()?;
+        let directed = self.is_directed();
+        let mut matrix = vec![vec![0; nodes.len()]; nodes.len()];
         
-        if result == 0 {
-            Ok(())
-        } else {
-            Err(GraphvizError::EdgeCreationFailed)
+        for (tail, head) in edges {
+            matrix[tail][head] += 1;
+            
+            if !directed && tail != head {
+                matrix[head][tail] += 1;
+            }
         }
+        
+        Ok((names, matrix))
     }
     
-    /// Gets the name of the graph.
+    /// Counts how many nodes have each value of an attribute.
+    ///
+    /// Values inherited from the graph's node defaults are counted like
+    /// explicitly set ones. Nodes where the attribute is unset or empty are
+    /// skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `attr` - The attribute name, e.g. "color"
     ///
     /// # Returns
     ///
-    /// The name of the graph as a String
-    pub fn name(&self) -> Result<String, GraphvizError> {
-        let name = unsafe { 
-            sys::agnameof(self.inner as *mut _) 
-        };
+    /// A Result containing the number of nodes per value, or an error
+    pub fn attribute_value_counts(&self, attr: &str) -> Result<HashMap<String, usize>, GraphvizError> {
+        let mut counts = HashMap::new();
         
-        if name.is_null() {
-            return Err(GraphvizError::NullPointer("Graph name is null"));
+        self.for_each_node(|node| {
+            count_value(&mut counts, node.get_attribute(attr)?);
+            Ok(())
+        })?;
+        
+        Ok(counts)
+    }
+    
+    /// Counts how many edges have each value of an attribute.
+    ///
+    /// Values inherited from the graph's edge defaults are counted like
+    /// explicitly set ones. Edges where the attribute is unset or empty are
+    /// skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `attr` - The attribute name, e.g. "color"
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the number of edges per value, or an error
+    pub fn edge_attribute_value_counts(&self, attr: &str) -> Result<HashMap<String, usize>, GraphvizError> {
+        let mut counts = HashMap::new();
+        
+        self.for_each_edge(|edge| {
+            count_value(&mut counts, edge.get_attribute(attr)?);
+            Ok(())
+        })?;
+        
+        Ok(counts)
+    }
+    
+    /// Dumps every node's position and size as CSV, for debugging layouts.
+    ///
+    /// The output has a `name,x,y,width,height` header followed by one row
+    /// per node in `nodes()` order. Positions are node centers and all values
+    /// are in points, with the origin at the lower-left corner of the drawing.
+    ///
+    /// GraphViz only records positions in the graph's attributes when it
+    /// writes DOT output, so the graph is serialized to DOT in memory first.
+    /// This leaves `pos`, `width` and `height` set on every node, `pos` on
+    /// every edge and `bb` on the graph, as `Context::compute_layout` does.
+    /// They then appear in `GraphData::from_graph`, `Graph::diff` and
+    /// `RenderCache` keys like any other attribute.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the CSV text, or `GraphvizError::LayoutFailed` if
+    /// the graph has no active layout
+    pub fn dump_layout(&self) -> Result<String, GraphvizError> {
+        let mut csv = String::from("name,x,y,width,height\n");
+        
+        for (name, (x, y), (width, height)) in self.node_layout()? {
+            let name = if name.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", name.replace('"', "\"\""))
+            } else {
+                name
+            };
+            
+            csv.push_str(&format!("{},{},{},{},{}\n", name, x, y, width, height));
         }
         
-        let c_str = unsafe { CStr::from_ptr(name) };
-        let name_str = c_str.to_str()
-            .map_err(|_| GraphvizError::InvalidUtf8)?
-            .to_owned();
+        Ok(csv)
+    }
+    
+    /// Dumps every node's position and size as a JSON array, for debugging layouts.
+    ///
+    /// Each element is an object with `name`, `x`, `y`, `width` and `height`
+    /// fields, in the units described for `dump_layout`, and leaves the same
+    /// layout attributes set on the graph.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the JSON text, or `GraphvizError::LayoutFailed` if
+    /// the graph has no active layout
+    #[cfg(feature = "serde")]
+    pub fn dump_layout_json(&self) -> Result<String, GraphvizError> {
+        let rows: Vec<_> = self.node_layout()?
+            .into_iter()
+            .map(|(name, (x, y), (width, height))| serde_json::json!({
+                "name": name,
+                "x": x,
+                "y": y,
+                "width": width,
+                "height": height,
+            }))
+            .collect();
         
-        Ok(name_str)
+        serde_json::to_string(&rows).map_err(|_| GraphvizError::InvalidJson)
     }
     
-    /// Checks if the graph is directed.
+    /// Sets the `label` of every node from a closure, leaving node names unchanged.
+    ///
+    /// This suits graphs whose node names are stable identifiers while the
+    /// rendered output should show human-readable text.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure producing the label for each node
     ///
     /// # Returns
     ///
-    /// true if the graph is directed, false otherwise
-    pub fn is_directed(&self) -> bool {
-        unsafe { sys::agisdirected(self.inner) != 0 }
+    /// Result indicating success or the first error
+    pub fn set_labels_from<F: Fn(&Node) -> String>(&self, f: F) -> Result<(), GraphvizError> {
+        self.for_each_node(|node| node.set_attribute(attr::node::LABEL, &f(node)))
     }
     
-    /// Checks if the graph is strict (no duplicate edges).
+    /// Sets the `label` of every edge from a closure.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure producing the label for each edge
     ///
     /// # Returns
     ///
-    /// true if the graph is strict, false otherwise
-    pub fn is_strict(&self) -> bool {
-        unsafe { sys::agisstrict(self.inner) != 0 }
+    /// Result indicating success or the first error
+    pub fn set_edge_labels_from<F: Fn(&Edge) -> String>(&self, f: F) -> Result<(), GraphvizError> {
+        self.for_each_edge(|edge| edge.set_attribute(attr::edge::LABEL, &f(edge)))
     }
-}
-
-// NodeIter implementation
-impl<'a> Iterator for NodeIter<'a> {
-    type Item = Node<'a>;
     
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.next.is_null() {
-            return None;
-        }
+    /// Finds the first node matching a predicate, stopping as soon as one matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - The closure deciding whether a node matches
+    ///
+    /// # Returns
+    ///
+    /// The first matching node in iteration order, or None
+    pub fn find_node<F: FnMut(&Node) -> bool>(&self, mut predicate: F) -> Option<Node<'_>> {
+        self.nodes().find(|node| predicate(node))
+    }
+    
+    /// Returns the first non-None value produced by a closure over the nodes.
+    ///
+    /// Iteration stops as soon as the closure returns a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure mapping a node to an optional value
+    ///
+    /// # Returns
+    ///
+    /// The first value produced, or None
+    pub fn find_map_node<T, F: FnMut(&Node) -> Option<T>>(&self, mut f: F) -> Option<T> {
+        self.nodes().find_map(|node| f(&node))
+    }
+    
+    /// Gets the number of nodes in the graph.
+    ///
+    /// # Returns
+    ///
+    /// The number of nodes in the graph
+    pub fn node_count(&self) -> i32 {
+        unsafe { sys::agnnodes(self.inner) }
+    }
+    
+    /// Checks whether the graph has no nodes.
+    ///
+    /// Rendering an empty graph produces a blank image; `validate` reports it
+    /// as `ValidationWarning::EmptyGraph`.
+    ///
+    /// # Returns
+    ///
+    /// true if the graph has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.node_count() == 0
+    }
+    
+    /// Gets the number of edges in the graph.
+    ///
+    /// # Returns
+    ///
+    /// The number of edges in the graph
+    pub fn edge_count(&self) -> i32 {
+        unsafe { sys::agnedges(self.inner) }
+    }
+    
+    /// Computes the density of the graph.
+    ///
+    /// Density is the number of edges between distinct nodes divided by the
+    /// number of possible ones: `m / (n * (n - 1))` for directed graphs and
+    /// `2m / (n * (n - 1))` for undirected graphs. Self-loops are not counted,
+    /// while every parallel edge is, so a multigraph can exceed 1.0.
+    ///
+    /// # Returns
+    ///
+    /// The density, or 0.0 for graphs with fewer than two nodes
+    pub fn density(&self) -> f64 {
+        let self_loops = self.nodes()
+            .map(|node| self.out_edges(&node).filter(|edge| edge.is_self_loop()).count())
+            .sum::<usize>();
         
-        let current = self.next;
-        self.next = unsafe { sys::agnxtnode(self.graph.inner, current) };
+        density_of(self.node_count() as usize, self.edge_count() as usize - self_loops, self.is_directed())
+    }
+    
+    /// Collects the graph's structural characteristics in a single snapshot.
+    ///
+    /// Nodes and edges are each visited once; the component count and
+    /// acyclicity check run in time linear in the size of the graph, so the
+    /// call is cheap enough to make before every render.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the statistics or an error
+    pub fn stats(&self) -> Result<GraphStats, GraphvizError> {
+        let (nodes, edges) = self.indexed_edges()?;
+        let directed = self.is_directed();
+        let self_loops = edges.iter().filter(|(tail, head)| tail == head).count();
+        let node_count = nodes.len();
+        let connected_components = count_components(node_count, &edges);
+        let acyclic = if directed {
+            is_dag(node_count, &edges)
+        } else {
+            // An undirected graph is a forest exactly when every edge joins two components
+            edges.len() + connected_components == node_count
+        };
         
-        Some(Node {
-            inner: current,
-            _phantom: PhantomData,
+        Ok(GraphStats {
+            node_count,
+            edge_count: edges.len(),
+            directed,
+            strict: self.is_strict(),
+            density: density_of(node_count, edges.len() - self_loops, directed),
+            connected_components,
+            acyclic,
         })
     }
-}
-
-// EdgeIter implementation
-impl<'a> Iterator for EdgeIter<'a> {
-    type Item = Edge<'a>;
     
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.next.is_null() {
-            // If we're iterating over all edges in the graph and current node is exhausted
-            if self.node.is_none() && !self.current_node.is_null() {
-                // Move to the next node
-                self.current_node = unsafe { sys::agnxtnode(self.graph.inner, self.current_node) };
-                
-                if self.current_node.is_null() {
-                    return None;
-                }
-                
-                // Get the first edge for the new node
-                self.next = unsafe { sys::agfstedge(self.graph.inner, self.current_node) };
-                
-                if self.next.is_null() {
-                    return self.next();  // Recursively try the next node
-                }
-            } else {
-                return None;
-            }
+    /// Computes the average degree of the nodes in the graph.
+    ///
+    /// Every edge adds one to the degree of each endpoint, so the average is
+    /// `2m / n` for both directed and undirected graphs. Parallel edges count
+    /// separately and a self-loop adds two to its node's degree.
+    ///
+    /// # Returns
+    ///
+    /// The average degree, or 0.0 for an empty graph
+    pub fn average_degree(&self) -> f64 {
+        let nodes = self.node_count();
+        
+        if nodes == 0 {
+            return 0.0;
         }
         
-        let current = self.next;
+        2.0 * self.edge_count() as f64 / nodes as f64
+    }
+    
+    /// Sets an attribute on the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_attribute(&self, name: &str, value: &str) -> Result<(), GraphvizError> {
+        let name = error::c_string(name, "attribute name")?;
+        let value = error::c_string(value, "attribute value")?;
         
-        // Get the next edge
-        self.next = if let Some(_node) = self.node {
-            if self.outgoing {
-                unsafe { sys::agnxtout(self.graph.inner, current) }
-            } else {
-                unsafe { sys::agnxtin(self.graph.inner, current) }
-            }
+        let sym = unsafe {
+            sys::agattr(
+                self.inner,
+                sys::AGRAPH as i32,
+                name.as_ptr() as *mut _,
+                value.as_ptr() as *mut _,
+            )
+        };
+        
+        if sym.is_null() {
+            return Err(GraphvizError::AttributeSetFailed);
+        }
+        
+        let result = unsafe { 
+            sys::agxset(self.inner as *mut _, sym, value.as_ptr() as *mut _) 
+        };
+        
+        if result == 0 {
+            Ok(())
         } else {
-            unsafe { sys::agnxtedge(self.graph.inner, current, self.current_node) }
+            Err(GraphvizError::AttributeSetFailed)
+        }
+    }
+    
+    /// Gets an attribute value from the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    ///
+    /// # Returns
+    ///
+    /// Option containing the attribute value if it exists
+    pub fn get_attribute(&self, name: &str) -> Result<Option<String>, GraphvizError> {
+        let name = error::c_string(name, "attribute name")?;
+        
+        let value = unsafe { 
+            sys::agget(self.inner as *mut _, name.as_ptr() as *mut _) 
         };
         
-        Some(Edge {
-            inner: current,
-            _phantom: PhantomData,
-        })
+        if value.is_null() {
+            return Ok(None);
+        }
+        
+        let c_str = unsafe { CStr::from_ptr(value) };
+        let value_str = c_str.to_str()
+            .map_err(|_| GraphvizError::InvalidUtf8)?
+            .to_owned();
+        
+        Ok(Some(value_str))
+    }
+    
+    /// Sets the graph label together with its placement.
+    ///
+    /// This sets `label`, `labelloc` and `labeljust` in one call.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The label text
+    /// * `loc` - The vertical placement of the label
+    /// * `just` - The horizontal justification of the label
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_title(&self, text: &str, loc: LabelLoc, just: LabelJust) -> Result<(), GraphvizError> {
+        self.set_attribute(attr::graph::LABEL, text)?;
+        self.set_attribute(attr::graph::LABELLOC, loc.as_str())?;
+        self.set_attribute(attr::graph::LABELJUST, just.as_str())
+    }
+    
+    /// Sets the direction in which ranks are laid out.
+    ///
+    /// # Arguments
+    ///
+    /// * `rankdir` - The layout direction
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_rankdir(&self, rankdir: attr::RankDir) -> Result<(), GraphvizError> {
+        self.set_attribute(attr::graph::RANKDIR, rankdir.as_str())
+    }
+    
+    /// Gets the direction in which ranks are laid out.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the direction, or None if `rankdir` is unset or
+    /// holds a value GraphViz would ignore
+    pub fn rankdir(&self) -> Result<Option<attr::RankDir>, GraphvizError> {
+        Ok(self.get_attribute(attr::graph::RANKDIR)?
+            .as_deref()
+            .and_then(attr::RankDir::parse))
+    }
+    
+    /// Draws an image behind the graph, e.g. as a watermark.
+    ///
+    /// This writes an xdot image operation (`I x y w h n -path`) to the
+    /// graph's `_background` attribute, replacing any existing background
+    /// drawing. Coordinates are in points (1/72 inch) in the layout's
+    /// coordinate system: the origin is the lower-left corner of the drawing
+    /// and y grows upward, as in the graph's `bb` attribute after layout. The
+    /// image is scaled to fill the box from (x, y) to (x + width, y + height).
+    ///
+    /// The file is read by the renderer, so it must be in a format the
+    /// renderer supports; SVG output only references the path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The image file
+    /// * `x` - The left edge of the image
+    /// * `y` - The bottom edge of the image
+    /// * `width` - The width of the image
+    /// * `height` - The height of the image
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_background_image<P: AsRef<Path>>(
+        &self,
+        path: P,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    ) -> Result<(), GraphvizError> {
+        let path = path.as_ref().to_string_lossy();
+        // Xdot text operands are length-prefixed by their size in bytes
+        let operation = format!("I {} {} {} {} {} -{}", x, y, width, height, path.len(), path);
+        
+        self.set_attribute(attr::graph::BACKGROUND, &operation)
+    }
+    
+    /// Sets how edges are routed.
+    ///
+    /// `Splines::Ortho` has extra requirements: dot ignores edge ports and
+    /// does not support edge labels with it (use `xlabel` instead), and large
+    /// graphs can take much longer to route.
+    ///
+    /// # Arguments
+    ///
+    /// * `splines` - The edge routing style
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_splines(&self, splines: attr::Splines) -> Result<(), GraphvizError> {
+        self.set_attribute(attr::graph::SPLINES, splines.as_str())
+    }
+    
+    /// Sets the directories searched for the font files used by this graph.
+    ///
+    /// This sets the `fontpath` graph attribute, which the renderers read
+    /// when looking up fonts for this graph only. Cairo-based renderers find
+    /// fonts through fontconfig instead; see `layout::set_font_config`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dirs` - The font directories, in search order
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_fontpath(&self, dirs: &[&Path]) -> Result<(), GraphvizError> {
+        let joined = std::env::join_paths(dirs).map_err(|err| {
+            GraphvizError::IoError(io::Error::new(io::ErrorKind::InvalidInput, err))
+        })?;
+        let joined = joined.to_str().ok_or(GraphvizError::InvalidUtf8)?;
+        
+        self.set_attribute(attr::graph::FONTPATH, joined)
+    }
+    
+    /// Controls whether every external label (`xlabel`) is drawn.
+    ///
+    /// When enabled, GraphViz places all external labels even if they overlap
+    /// nodes, edges or other labels; otherwise labels that cannot be placed
+    /// cleanly are omitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `force` - Whether to draw every external label
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_force_labels(&self, force: bool) -> Result<(), GraphvizError> {
+        self.set_attribute(attr::graph::FORCELABELS, if force { "true" } else { "false" })
+    }
+    
+    /// Keeps the edges around every node in creation order during layout.
+    ///
+    /// Without this, dot may reorder edges between runs, so rendered output is
+    /// not byte-for-byte reproducible.
+    ///
+    /// # Arguments
+    ///
+    /// * `ordering` - Whether incoming or outgoing edges keep their order
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_ordering(&self, ordering: attr::Ordering) -> Result<(), GraphvizError> {
+        self.set_attribute(attr::graph::ORDERING, ordering.as_str())
+    }
+    
+    /// Applies a color and font scheme to the graph and everything in it.
+    ///
+    /// The background and label font are set on the graph, and the fill,
+    /// border and font colors become the default node and edge attributes.
+    /// Nodes are given the `filled` style by default so the fill color shows.
+    /// Nodes and edges that set one of these attributes themselves keep
+    /// their own value, whether they were added before or after the call.
+    ///
+    /// # Arguments
+    ///
+    /// * `theme` - The theme to apply
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn apply_theme(&self, theme: &attr::Theme) -> Result<(), GraphvizError> {
+        self.set_attribute(attr::graph::BGCOLOR, &theme.background)?;
+        self.set_attribute(attr::graph::FONTNAME, &theme.font_name)?;
+        self.set_attribute(attr::graph::FONTCOLOR, &theme.font_color)?;
+        
+        self.set_default_attribute(sys::AGNODE, attr::node::STYLE, attr::values::style::FILLED)?;
+        self.set_default_attribute(sys::AGNODE, attr::node::FILLCOLOR, &theme.node_fill)?;
+        self.set_default_attribute(sys::AGNODE, attr::node::COLOR, &theme.node_border)?;
+        self.set_default_attribute(sys::AGNODE, attr::node::FONTNAME, &theme.font_name)?;
+        self.set_default_attribute(sys::AGNODE, attr::node::FONTCOLOR, &theme.font_color)?;
+        
+        self.set_default_attribute(sys::AGEDGE, attr::edge::COLOR, &theme.edge_color)?;
+        self.set_default_attribute(sys::AGEDGE, attr::edge::FONTNAME, &theme.font_name)?;
+        self.set_default_attribute(sys::AGEDGE, attr::edge::FONTCOLOR, &theme.font_color)
+    }
+    
+    /// Gets the default node attributes declared on the graph.
+    ///
+    /// These are the values GraphViz applies to nodes that do not set the
+    /// attribute themselves. Attributes declared with an empty default are
+    /// omitted.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the attribute name/default pairs or an error
+    pub fn default_node_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        unsafe { attribute_defaults(self.inner, sys::AGNODE) }
+    }
+    
+    /// Gets the default edge attributes declared on the graph.
+    ///
+    /// These are the values GraphViz applies to edges that do not set the
+    /// attribute themselves. Attributes declared with an empty default are
+    /// omitted.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the attribute name/default pairs or an error
+    pub fn default_edge_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        unsafe { attribute_defaults(self.inner, sys::AGEDGE) }
+    }
+    
+    /// Removes a node from the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to remove
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn remove_node(&self, node: Node) -> Result<(), GraphvizError> {
+        self.remove_node_ref(&node)
+    }
+    
+    /// Removes a node from the graph without consuming the handle.
+    ///
+    /// This is convenient when node handles were collected up front, e.g. in a loop.
+    /// The node's incident edges are removed as well. After removal, the handle and
+    /// any copies of it (including handles to the removed edges) are dangling, and
+    /// using them is undefined behavior.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to remove
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn remove_node_ref(&self, node: &Node) -> Result<(), GraphvizError> {
+        let result = unsafe { 
+            sys::agdelnode(self.inner, node.inner) 
+        };
+        
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(GraphvizError::NodeCreationFailed)
+        }
+    }
+    
+    /// Removes every node that has no incident edges.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the number of nodes removed or an error
+    pub fn remove_isolated_nodes(&self) -> Result<usize, GraphvizError> {
+        // Collect first: deleting nodes while iterating invalidates the cursor
+        let isolated: Vec<Node> = self.nodes()
+            .filter(|node| unsafe { sys::agdegree(self.inner, node.inner, 1, 1) } == 0)
+            .collect();
+        
+        for node in &isolated {
+            self.remove_node_ref(node)?;
+        }
+        
+        Ok(isolated.len())
+    }
+    
+    /// Removes an edge from the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - The edge to remove
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn remove_edge(&self, edge: Edge) -> Result<(), GraphvizError> {
+        self.remove_edge_ref(&edge)
+    }
+    
+    /// Removes an edge from the graph without consuming the handle.
+    ///
+    /// After removal, the handle and any copies of it are dangling, and using them
+    /// is undefined behavior.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - The edge to remove
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn remove_edge_ref(&self, edge: &Edge) -> Result<(), GraphvizError> {
+        let result = unsafe { 
+            sys::agdeledge(self.inner, edge.inner) 
+        };
+        
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(GraphvizError::EdgeCreationFailed)
+        }
+    }
+    
+    /// Reverses the direction of an edge.
+    ///
+    /// GraphViz edges cannot be modified in place, so the edge is deleted and
+    /// recreated with swapped endpoints, keeping its name and the attributes set on it.
+    /// The original handle is consumed; any other handle to the same edge is
+    /// invalidated as well and must not be used afterward.
+    ///
+    /// In a strict graph, recreating the edge would silently merge it into an
+    /// existing edge in the opposite direction, so that case is rejected before
+    /// anything is deleted and the graph is left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - The edge to reverse
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Edge or an error, including
+    /// `GraphvizError::DuplicateEdge` if the graph is strict and already has
+    /// an edge from the head to the tail
+    pub fn reverse_edge(&self, edge: Edge) -> Result<Edge<'_>, GraphvizError> {
+        let tail = edge.tail();
+        let head = edge.head();
+        let name = edge.key()?;
+        
+        if self.is_strict() {
+            if let Some(existing) = self.find_edge(&head, &tail) {
+                if existing.inner != edge.inner {
+                    return Err(GraphvizError::DuplicateEdge(head.name()?, tail.name()?));
+                }
+            }
+        }
+        
+        let attributes = unsafe { local_attributes(sys::AGEDGE, edge.inner as *mut _)? };
+        
+        self.remove_edge(edge)?;
+        
+        let reversed = self.add_edge(&head, &tail, name.as_deref())?;
+        unsafe { set_attribute_values(reversed.inner as *mut _, &attributes)? };
+        
+        Ok(reversed)
+    }
+    
+    /// Renames a node, keeping its attributes, incident edges, and subgraph memberships.
+    ///
+    /// GraphViz cannot rename nodes in place, so a node with the new name is
+    /// created first, added to every subgraph and cluster the original belongs
+    /// to, and given copies of the original's attributes and incident edges
+    /// (with their names, directions, attributes, and subgraph memberships).
+    /// Only then is the original deleted, so if any step fails the graph is
+    /// left unchanged. The original handle and handles to its edges are
+    /// invalidated on success and must not be used afterward.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to rename
+    /// * `new_name` - The new name, which must not belong to another node
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the renamed Node or an error, including
+    /// `GraphvizError::DuplicateName` if another node already has `new_name`
+    pub fn rename_node(&self, node: &Node, new_name: &str) -> Result<Node<'_>, GraphvizError> {
+        if node.name()? == new_name {
+            return Ok(Node { inner: node.inner, _phantom: PhantomData });
+        }
+        
+        if self.get_node(new_name)?.is_some() {
+            return Err(GraphvizError::DuplicateName(new_name.to_owned()));
+        }
+        
+        let mut edges: Vec<_> = self.out_edges(node).map(|edge| edge.inner).collect();
+        
+        for edge in self.in_edges(node) {
+            // Self-loops were already collected as outgoing edges
+            if edge.tail().inner != node.inner {
+                edges.push(edge.inner);
+            }
+        }
+        
+        let renamed = self.add_node(new_name)?;
+        
+        if let Err(error) = self.copy_renamed_node(node, &renamed, &edges) {
+            self.remove_node_ref(&renamed)?;
+            return Err(error);
+        }
+        
+        self.remove_node_ref(node)?;
+        Ok(renamed)
+    }
+    
+    /// Gets the name of the graph.
+    ///
+    /// # Returns
+    ///
+    /// The name of the graph as a String
+    pub fn name(&self) -> Result<String, GraphvizError> {
+        let name = unsafe { 
+            sys::agnameof(self.inner as *mut _) 
+        };
+        
+        if name.is_null() {
+            return Err(GraphvizError::NullPointer("Graph name is null"));
+        }
+        
+        let c_str = unsafe { CStr::from_ptr(name) };
+        let name_str = c_str.to_str()
+            .map_err(|_| GraphvizError::InvalidUtf8)?
+            .to_owned();
+        
+        Ok(name_str)
+    }
+    
+    /// Checks if the graph is directed.
+    ///
+    /// # Returns
+    ///
+    /// true if the graph is directed, false otherwise
+    pub fn is_directed(&self) -> bool {
+        unsafe { sys::agisdirected(self.inner) != 0 }
+    }
+    
+    /// Checks if the graph is strict (no duplicate edges).
+    ///
+    /// # Returns
+    ///
+    /// true if the graph is strict, false otherwise
+    pub fn is_strict(&self) -> bool {
+        unsafe { sys::agisstrict(self.inner) != 0 }
+    }
+    
+    /// Gets the kind of the graph, combining its direction and strictness.
+    ///
+    /// # Returns
+    ///
+    /// The GraphKind matching the descriptor the graph was created with
+    pub fn kind(&self) -> GraphKind {
+        match (self.is_directed(), self.is_strict()) {
+            (true, false) => GraphKind::Directed,
+            (true, true) => GraphKind::StrictDirected,
+            (false, false) => GraphKind::Undirected,
+            (false, true) => GraphKind::StrictUndirected,
+        }
+    }
+    
+    /// Creates a new graph with the direction of every edge reversed.
+    ///
+    /// Nodes, edge names, and attributes are copied to the new graph. Undirected
+    /// graphs have no edge direction, so for them the result is a plain copy.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the transposed Graph or an error
+    pub fn transpose(&self) -> Result<Graph, GraphvizError> {
+        let reverse = self.is_directed();
+        let transposed = self.empty_copy(self.is_strict())?;
+        
+        for node in self.nodes() {
+            transposed.import_node(&node)?;
+        }
+        
+        for node in self.nodes() {
+            for edge in self.out_edges(&node) {
+                let (tail, head) = if reverse {
+                    (edge.head(), edge.tail())
+                } else {
+                    (edge.tail(), edge.head())
+                };
+                
+                transposed.import_edge(&edge, &tail.name()?, &head.name()?)?;
+            }
+        }
+        
+        Ok(transposed)
+    }
+    
+    /// Creates a strict copy of the graph, merging parallel edges.
+    ///
+    /// GraphViz cannot change the strictness of an existing graph, so a new graph
+    /// is built. When several edges connect the same pair of nodes (in the same
+    /// direction, or in either direction for undirected graphs), only the first one
+    /// in iteration order is kept, together with its name and attributes.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the strict Graph or an error
+    pub fn to_strict(&self) -> Result<Graph, GraphvizError> {
+        let strict = self.empty_copy(true)?;
+        
+        for node in self.nodes() {
+            strict.import_node(&node)?;
+        }
+        
+        for node in self.nodes() {
+            for edge in self.out_edges(&node) {
+                let (tail, head) = (edge.tail().name()?, edge.head().name()?);
+                let exists = strict.find_edge(&strict.add_node(&tail)?, &strict.add_node(&head)?).is_some();
+                
+                if !exists {
+                    strict.import_edge(&edge, &tail, &head)?;
+                }
+            }
+        }
+        
+        Ok(strict)
+    }
+    
+    /// Creates a new graph containing only the nodes matching a predicate.
+    ///
+    /// Edges are kept when both of their endpoints are kept. Attributes are copied.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns true for nodes to keep
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the filtered Graph or an error
+    pub fn filter_nodes<F: Fn(&Node) -> bool>(&self, predicate: F) -> Result<Graph, GraphvizError> {
+        let filtered = self.empty_copy(self.is_strict())?;
+        let mut kept = HashSet::new();
+        
+        for node in self.nodes() {
+            if predicate(&node) {
+                filtered.import_node(&node)?;
+                kept.insert(node.inner);
+            }
+        }
+        
+        for node in self.nodes() {
+            for edge in self.out_edges(&node) {
+                let (tail, head) = (edge.tail(), edge.head());
+                
+                if kept.contains(&tail.inner) && kept.contains(&head.inner) {
+                    filtered.import_edge(&edge, &tail.name()?, &head.name()?)?;
+                }
+            }
+        }
+        
+        Ok(filtered)
+    }
+    
+    /// Creates a new graph containing all nodes but only the edges matching a predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns true for edges to keep
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the filtered Graph or an error
+    pub fn filter_edges<F: Fn(&Edge) -> bool>(&self, predicate: F) -> Result<Graph, GraphvizError> {
+        let filtered = self.empty_copy(self.is_strict())?;
+        
+        for node in self.nodes() {
+            filtered.import_node(&node)?;
+        }
+        
+        for node in self.nodes() {
+            for edge in self.out_edges(&node) {
+                if predicate(&edge) {
+                    filtered.import_edge(&edge, &edge.tail().name()?, &edge.head().name()?)?;
+                }
+            }
+        }
+        
+        Ok(filtered)
+    }
+    
+    /// Creates a copy of the graph in which parallel edges are merged into one.
+    ///
+    /// Edges are parallel when they join the same nodes; in undirected graphs
+    /// the order of the endpoints does not matter. Each group of two or more
+    /// parallel edges becomes a single edge whose attributes are exactly those
+    /// returned by `combine`, for example a summed `weight` or a joined
+    /// `label`. Edges without parallels are copied unchanged, as are all nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `combine` - Computes the attributes of the merged edge from a group
+    ///   of parallel edges, given in creation order
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Graph or an error
+    pub fn collapse_parallel_edges<F>(&self, combine: F) -> Result<Graph, GraphvizError>
+    where
+        F: Fn(&[Edge]) -> HashMap<String, String>,
+    {
+        let collapsed = self.empty_copy(self.is_strict())?;
+        let directed = self.is_directed();
+        let mut groups: Vec<Vec<Edge>> = Vec::new();
+        let mut group_index: HashMap<_, usize> = HashMap::new();
+        let nodes: Vec<_> = self.nodes().collect();
+        
+        for node in &nodes {
+            collapsed.import_node(node)?;
+            
+            for edge in self.out_edges(node) {
+                let (tail, head) = (edge.tail().inner, edge.head().inner);
+                let key = if directed || tail <= head { (tail, head) } else { (head, tail) };
+                
+                match group_index.entry(key) {
+                    Entry::Occupied(entry) => groups[*entry.get()].push(edge),
+                    Entry::Vacant(entry) => {
+                        entry.insert(groups.len());
+                        groups.push(vec![edge]);
+                    }
+                }
+            }
+        }
+        
+        for group in &groups {
+            let (tail, head) = (group[0].tail().name()?, group[0].head().name()?);
+            
+            if let [edge] = &group[..] {
+                collapsed.import_edge(edge, &tail, &head)?;
+                continue;
+            }
+            
+            let merged = collapsed.add_edge_by_name(&tail, &head, None)?;
+            
+            for (name, value) in combine(group) {
+                merged.set_attribute(&name, &value)?;
+            }
+        }
+        
+        Ok(collapsed)
+    }
+    
+    /// Creates a copy of the graph with its transitive closure added.
+    ///
+    /// Whenever a node B is reachable from a node A, the copy has an edge
+    /// from A to B. Existing nodes and edges keep their attributes; the added
+    /// edges have none. Nodes on a cycle all reach each other, so every member
+    /// of a cycle gains edges to every other member, but no self-loops are
+    /// added. In an undirected graph each connected component becomes complete.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Graph or an error
+    pub fn transitive_closure(&self) -> Result<Graph, GraphvizError> {
+        let (nodes, edges) = self.indexed_edges()?;
+        let names = nodes.iter().map(Node::name).collect::<Result<Vec<_>, _>>()?;
+        let directed = self.is_directed();
+        let successors = successor_lists(nodes.len(), &edges, directed);
+        let existing: HashSet<_> = successors.iter()
+            .enumerate()
+            .flat_map(|(tail, heads)| heads.iter().map(move |&head| (tail, head)))
+            .collect();
+        let closure = self.filter_edges(|_| true)?;
+        
+        for tail in 0..nodes.len() {
+            for (head, reached) in reachable_from(&successors, tail).into_iter().enumerate() {
+                if !reached || head == tail || existing.contains(&(tail, head)) {
+                    continue;
+                }
+                
+                // Undirected pairs are visited in both orders; add each edge once
+                if !directed && head < tail {
+                    continue;
+                }
+                
+                closure.add_edge_by_name(&names[tail], &names[head], None)?;
+            }
+        }
+        
+        Ok(closure)
+    }
+    
+    /// Creates a copy of the graph with every edge implied by transitivity removed.
+    ///
+    /// An edge from A to C is dropped when C can also be reached from A through
+    /// other nodes, so the copy has the fewest edges that preserve reachability.
+    /// Parallel edges collapse to the first one, and the remaining nodes and
+    /// edges keep their attributes. Like GraphViz's `tred` tool this only
+    /// applies to directed graphs; an undirected graph is copied unchanged.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Graph, or `GraphvizError::CyclicGraph` if the
+    /// graph contains a cycle, for which the minimal edge set is not unique
+    pub fn transitive_reduction(&self) -> Result<Graph, GraphvizError> {
+        if !self.is_directed() {
+            return self.filter_edges(|_| true);
+        }
+        
+        let (nodes, edges) = self.indexed_edges()?;
+        
+        if !is_dag(nodes.len(), &edges) {
+            return Err(GraphvizError::CyclicGraph);
+        }
+        
+        let successors = successor_lists(nodes.len(), &edges, true);
+        let mut needed = HashSet::new();
+        
+        for (tail, heads) in successors.iter().enumerate() {
+            // Nodes reachable through at least one intermediate node
+            let indirect: Vec<_> = heads.iter()
+                .map(|&head| reachable_from(&successors, head))
+                .collect();
+            
+            for &head in heads {
+                if !indirect.iter().any(|reached| reached[head]) {
+                    needed.insert((nodes[tail].inner, nodes[head].inner));
+                }
+            }
+        }
+        
+        let copied = RefCell::new(HashSet::new());
+        
+        self.filter_edges(|edge| {
+            let pair = (edge.tail().inner, edge.head().inner);
+            needed.contains(&pair) && copied.borrow_mut().insert(pair)
+        })
+    }
+    
+    /// Compares this graph with another.
+    ///
+    /// See `GraphDiff` for how nodes and edges are matched.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to compare against this one
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the changes from this graph to `other`, or an error
+    pub fn diff(&self, other: &Graph) -> Result<GraphDiff, GraphvizError> {
+        GraphDiff::between(self, other)
+    }
+    
+    /// Checks the graph for common problems before it is laid out.
+    ///
+    /// This catches mistakes during graph construction that GraphViz would
+    /// otherwise report opaquely or silently ignore at layout time: an empty
+    /// graph, and numeric attributes such as `penwidth` or `fontsize` with
+    /// negative or non-numeric values. Edges to nodes of another graph are
+    /// rejected earlier, by `add_edge`.
+    ///
+    /// # Returns
+    ///
+    /// Ok if no problems were found, otherwise every warning that was found
+    pub fn validate(&self) -> Result<(), Vec<ValidationWarning>> {
+        let mut warnings = Vec::new();
+        
+        if self.is_empty() {
+            warnings.push(ValidationWarning::EmptyGraph);
+        }
+        
+        check_attributes("graph".to_owned(), self.graph_attributes(), &mut warnings);
+        
+        for node in self.nodes() {
+            let name = node.name().unwrap_or_default();
+            check_attributes(format!("node {}", display_id(&name)), node.local_attributes(), &mut warnings);
+            
+            for edge in self.out_edges(&node) {
+                let tail_name = edge.tail().name().unwrap_or_default();
+                let head_name = edge.head().name().unwrap_or_default();
+                let target = format!("edge {} -> {}", display_id(&tail_name), display_id(&head_name));
+                check_attributes(target, edge.local_attributes(), &mut warnings);
+            }
+        }
+        
+        if warnings.is_empty() {
+            Ok(())
+        } else {
+            Err(warnings)
+        }
+    }
+    
+    /// Finds a shortest path between two nodes by edge count.
+    ///
+    /// Edges are followed in their direction for directed graphs and in both
+    /// directions for undirected graphs.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The node the path starts at
+    /// * `to` - The node the path ends at
+    ///
+    /// # Returns
+    ///
+    /// The nodes along the path including both endpoints, a single-node path
+    /// if `from` and `to` are the same node, or None if `to` is unreachable
+    pub fn shortest_path(&self, from: &Node, to: &Node) -> Option<Vec<Node<'_>>> {
+        let mut previous = HashMap::new();
+        let mut queue = VecDeque::new();
+        previous.insert(from.inner, ptr::null_mut());
+        queue.push_back(from.inner);
+        
+        while let Some(current) = queue.pop_front() {
+            if current == to.inner {
+                return Some(self.trace_path(&previous, to.inner));
+            }
+            
+            let node = Node { inner: current, _phantom: PhantomData };
+            
+            for (_, next) in self.traversable_edges(&node) {
+                if let Entry::Vacant(entry) = previous.entry(next) {
+                    entry.insert(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        
+        None
+    }
+    
+    /// Reads each node's position and size from the active layout, in points.
+    ///
+    /// The layout attributes are attached to the graph first and left set.
+    fn node_layout(&self) -> Result<Vec<NodeGeometry>, GraphvizError> {
+        let context = self.layout_context.as_ref().ok_or(GraphvizError::LayoutFailed)?;
+        layout::attach_layout_attributes(context, self)?;
+        
+        self.nodes().map(|node| layout::node_geometry(&node)).collect()
+    }
+    
+    /// Lists the nodes together with every edge as a (tail, head) pair of node indices.
+    ///
+    /// Nodes are in `nodes()` order and each edge appears once.
+    fn indexed_edges(&self) -> Result<IndexedEdges<'_>, GraphvizError> {
+        let nodes: Vec<_> = self.nodes().collect();
+        let index: HashMap<_, _> = nodes.iter()
+            .enumerate()
+            .map(|(i, node)| (node.inner, i))
+            .collect();
+        let mut edges = Vec::new();
+        
+        for node in &nodes {
+            for edge in self.out_edges(node) {
+                let tail = index.get(&edge.tail().inner).copied();
+                let head = index.get(&edge.head().inner).copied();
+                let (Some(tail), Some(head)) = (tail, head) else {
+                    return Err(GraphvizError::NullPointer("Edge endpoint outside graph"));
+                };
+                
+                edges.push((tail, head));
+            }
+        }
+        
+        Ok((nodes, edges))
+    }
+    
+    /// Finds a cheapest path between two nodes using Dijkstra's algorithm.
+    ///
+    /// The cost of each edge is read from a numeric edge attribute, usually
+    /// `"weight"`. Edges without the attribute cost 1.0. Edges are followed in
+    /// their direction for directed graphs and in both directions for
+    /// undirected graphs.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The node the path starts at
+    /// * `to` - The node the path ends at
+    /// * `weight_attr` - The edge attribute holding the cost
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the nodes along the path and its total cost, None if
+    /// `to` is unreachable, or `GraphvizError::InvalidWeight` if a traversed
+    /// edge has a negative or non-numeric cost
+    pub fn shortest_path_weighted(
+        &self,
+        from: &Node,
+        to: &Node,
+        weight_attr: &str,
+    ) -> Result<Option<(Vec<Node<'_>>, f64)>, GraphvizError> {
+        let mut previous = HashMap::new();
+        let mut costs = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut queue = BinaryHeap::new();
+        previous.insert(from.inner, ptr::null_mut());
+        costs.insert(from.inner, 0.0);
+        queue.push(PathCandidate { cost: 0.0, node: from.inner });
+        
+        while let Some(PathCandidate { cost, node: current }) = queue.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            
+            if current == to.inner {
+                return Ok(Some((self.trace_path(&previous, to.inner), cost)));
+            }
+            
+            let node = Node { inner: current, _phantom: PhantomData };
+            
+            for (edge, next) in self.traversable_edges(&node) {
+                let weight = match edge.get_attribute(weight_attr)? {
+                    Some(value) if !value.is_empty() => parse_weight(&value)?,
+                    _ => 1.0,
+                };
+                let next_cost = cost + weight;
+                
+                if costs.get(&next).is_none_or(|&known| next_cost < known) {
+                    costs.insert(next, next_cost);
+                    previous.insert(next, current);
+                    queue.push(PathCandidate { cost: next_cost, node: next });
+                }
+            }
+        }
+        
+        Ok(None)
+    }
+    
+    /// Gets the edges leaving a node along with the node at their other end.
+    ///
+    /// For undirected graphs incoming edges are included as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node whose edges to follow
+    ///
+    /// # Returns
+    ///
+    /// The traversable edges paired with the node they lead to
+    fn traversable_edges<'a>(&'a self, node: &'a Node) -> Vec<(Edge<'a>, *mut sys::Agnode_t)> {
+        let mut edges: Vec<_> = self.out_edges(node)
+            .map(|edge| {
+                let head = edge.head().inner;
+                (edge, head)
+            })
+            .collect();
+        
+        if !self.is_directed() {
+            for edge in self.in_edges(node) {
+                let tail = edge.tail().inner;
+                
+                // Self-loops were already reached through the outgoing edges
+                if tail != node.inner {
+                    edges.push((edge, tail));
+                }
+            }
+        }
+        
+        edges
+    }
+    
+    /// Rebuilds a path by walking predecessor links back from its last node.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - Maps each reached node to the node it was reached from,
+    ///   with a null pointer marking the start
+    /// * `last` - The node the path ends at
+    ///
+    /// # Returns
+    ///
+    /// The nodes along the path in order from start to end
+    fn trace_path(
+        &self,
+        previous: &HashMap<*mut sys::Agnode_t, *mut sys::Agnode_t>,
+        last: *mut sys::Agnode_t,
+    ) -> Vec<Node<'_>> {
+        let mut path = Vec::new();
+        let mut current = last;
+        
+        while !current.is_null() {
+            path.push(Node { inner: current, _phantom: PhantomData });
+            current = previous.get(&current).copied().unwrap_or(ptr::null_mut());
+        }
+        
+        path.reverse();
+        path
+    }
+    
+    /// Gets every graph attribute with a non-empty value.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the attribute name/value pairs or an error
+    pub(crate) fn graph_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        let mut attributes = Vec::new();
+        let mut sym = unsafe { sys::agnxtattr(self.inner, sys::AGRAPH as i32, ptr::null_mut()) };
+        
+        while !sym.is_null() {
+            let value = unsafe { sys::agxget(self.inner as *mut _, sym) };
+            
+            if !value.is_null() {
+                let value = unsafe { CStr::from_ptr(value) }.to_str()?;
+                
+                if !value.is_empty() {
+                    let name = unsafe { CStr::from_ptr((*sym).name) }.to_str()?;
+                    attributes.push((name.to_owned(), value.to_owned()));
+                }
+            }
+            
+            sym = unsafe { sys::agnxtattr(self.inner, sys::AGRAPH as i32, sym) };
+        }
+        
+        Ok(attributes)
+    }
+    
+    /// Creates an empty graph with this graph's name, direction, and attribute declarations.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - Whether the new graph is strict
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Graph or an error
+    fn empty_copy(&self, strict: bool) -> Result<Graph, GraphvizError> {
+        let copy = Graph::new_with_strictness(&self.name()?, self.is_directed(), strict)?;
+        
+        for kind in [sys::AGRAPH, sys::AGNODE, sys::AGEDGE] {
+            unsafe { copy_attribute_declarations(self.inner, copy.inner, kind)? };
+        }
+        
+        Ok(copy)
+    }
+    
+    /// Sets the default value of a node or edge attribute.
+    ///
+    /// Redeclaring an attribute only changes the value given to objects created
+    /// later, so existing objects still holding the previous default are
+    /// updated as well. Objects with a value of their own are left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - `sys::AGNODE` or `sys::AGEDGE`
+    /// * `name` - The attribute name
+    /// * `value` - The new default value
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    fn set_default_attribute(&self, kind: u32, name: &str, value: &str) -> Result<(), GraphvizError> {
+        let name = error::c_string(name, "attribute name")?;
+        let value = error::c_string(value, "attribute value")?;
+        
+        let declared = unsafe {
+            sys::agattr(self.inner, kind as i32, name.as_ptr() as *mut _, ptr::null_mut())
+        };
+        let previous = (!declared.is_null())
+            .then(|| unsafe { CStr::from_ptr((*declared).defval) }.to_owned());
+        
+        let sym = unsafe {
+            sys::agattr(self.inner, kind as i32, name.as_ptr() as *mut _, value.as_ptr() as *mut _)
+        };
+        
+        if sym.is_null() {
+            return Err(GraphvizError::AttributeSetFailed);
+        }
+        
+        let Some(previous) = previous else {
+            return Ok(());
+        };
+        
+        let mut objects: Vec<*mut c_void> = Vec::new();
+        
+        for node in self.nodes() {
+            if kind == sys::AGNODE {
+                objects.push(node.inner as *mut _);
+            } else {
+                objects.extend(self.out_edges(&node).map(|edge| edge.inner as *mut c_void));
+            }
+        }
+        
+        for object in objects {
+            let current = unsafe { sys::agxget(object, sym) };
+            let on_default = !current.is_null() && unsafe { CStr::from_ptr(current) } == previous.as_c_str();
+            
+            if on_default && unsafe { sys::agxset(object, sym, value.as_ptr() as *mut _) } != 0 {
+                return Err(GraphvizError::AttributeSetFailed);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Gives a renamed node the attributes, subgraph memberships, and edges of the original.
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - The node being renamed
+    /// * `renamed` - The newly created node taking its place
+    /// * `edges` - The edges incident to `old`, each listed once
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    fn copy_renamed_node(&self, old: &Node, renamed: &Node, edges: &[*mut sys::Agedge_t]) -> Result<(), GraphvizError> {
+        unsafe { copy_attribute_values(sys::AGNODE, old.inner as *mut _, renamed.inner as *mut _)? };
+        
+        for subgraph in subgraphs_containing(self.inner, |subgraph| unsafe { !sys::agsubnode(subgraph, old.inner, 0).is_null() }) {
+            if unsafe { sys::agsubnode(subgraph, renamed.inner, 1) }.is_null() {
+                return Err(GraphvizError::NodeCreationFailed);
+            }
+        }
+        
+        for &inner in edges {
+            let edge = Edge { inner, _phantom: PhantomData };
+            let (tail, head) = (edge.tail(), edge.head());
+            let tail = if tail.inner == old.inner { renamed } else { &tail };
+            let head = if head.inner == old.inner { renamed } else { &head };
+            
+            let copy = self.add_edge(tail, head, edge.key()?.as_deref())?;
+            unsafe { copy_attribute_values(sys::AGEDGE, inner as *mut _, copy.inner as *mut _)? };
+            
+            for subgraph in subgraphs_containing(self.inner, |subgraph| unsafe { !sys::agsubedge(subgraph, inner, 0).is_null() }) {
+                if unsafe { sys::agsubedge(subgraph, copy.inner, 1) }.is_null() {
+                    return Err(GraphvizError::EdgeCreationFailed);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Checks that a node belongs to this graph's root graph.
+    ///
+    /// cgraph does not reject nodes from other graphs when creating edges; it
+    /// silently builds an edge that is not registered with its endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to check
+    ///
+    /// # Returns
+    ///
+    /// Ok if the node belongs to this graph's root, otherwise `GraphvizError::ForeignNode`
+    fn check_same_root(&self, node: &Node) -> Result<(), GraphvizError> {
+        let same_root = unsafe {
+            sys::agroot(node.inner as *mut _) == sys::agroot(self.inner as *mut _)
+        };
+        
+        if same_root {
+            Ok(())
+        } else {
+            Err(GraphvizError::ForeignNode(node.name().unwrap_or_default()))
+        }
+    }
+    
+    /// Adds a copy of a node from another graph, including the attributes set on it.
+    ///
+    /// The attributes must already be declared on this graph (see `empty_copy`).
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to copy
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Node or an error
+    fn import_node(&self, node: &Node) -> Result<Node<'_>, GraphvizError> {
+        let copy = self.add_node(&node.name()?)?;
+        unsafe { copy_attribute_values(sys::AGNODE, node.inner as *mut _, copy.inner as *mut _)? };
+        Ok(copy)
+    }
+    
+    /// Adds a copy of an edge from another graph between the named endpoints.
+    ///
+    /// Missing endpoints are created. The edge keeps its name and the attributes set on it,
+    /// which must already be declared on this graph (see `empty_copy`).
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - The edge to copy
+    /// * `tail` - The name of the source node in this graph
+    /// * `head` - The name of the target node in this graph
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Edge or an error
+    fn import_edge(&self, edge: &Edge, tail: &str, head: &str) -> Result<Edge<'_>, GraphvizError> {
+        let tail = self.add_node(tail)?;
+        let head = self.add_node(head)?;
+        let copy = self.add_edge(&tail, &head, edge.key()?.as_deref())?;
+        unsafe { copy_attribute_values(sys::AGEDGE, edge.inner as *mut _, copy.inner as *mut _)? };
+        Ok(copy)
+    }
+}
+
+// NodeIter implementation
+impl<'a> Iterator for NodeIter<'a> {
+    type Item = Node<'a>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next.is_null() {
+            return None;
+        }
+        
+        let current = self.next;
+        self.next = unsafe { sys::agnxtnode(self.graph.inner, current) };
+        
+        Some(Node {
+            inner: current,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+// SubgraphIter implementation
+impl<'a> Iterator for SubgraphIter<'a> {
+    type Item = Subgraph<'a>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next.is_null() {
+            return None;
+        }
+        
+        let current = self.next;
+        self.next = unsafe { sys::agnxtsubg(current) };
+        
+        Some(Subgraph::from_raw(current))
+    }
+}
+
+// EdgeIter implementation
+impl<'a> Iterator for EdgeIter<'a> {
+    type Item = Edge<'a>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next.is_null() {
+            // If we're iterating over all edges in the graph and current node is exhausted
+            if self.node.is_none() && !self.current_node.is_null() {
+                // Move to the next node
+                self.current_node = unsafe { sys::agnxtnode(self.graph.inner, self.current_node) };
+                
+                if self.current_node.is_null() {
+                    return None;
+                }
+                
+                // Get the first edge for the new node
+                self.next = unsafe { sys::agfstedge(self.graph.inner, self.current_node) };
+                
+                if self.next.is_null() {
+                    return self.next();  // Recursively try the next node
+                }
+            } else {
+                return None;
+            }
+        }
+        
+        let current = self.next;
+        
+        // Get the next edge
+        self.next = if let Some(_node) = self.node {
+            if self.outgoing {
+                unsafe { sys::agnxtout(self.graph.inner, current) }
+            } else {
+                unsafe { sys::agnxtin(self.graph.inner, current) }
+            }
+        } else {
+            unsafe { sys::agnxtedge(self.graph.inner, current, self.current_node) }
+        };
+        
+        Some(Edge {
+            inner: current,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+// NodeBuilder implementation
+impl<'a> NodeBuilder<'a> {
+    /// Creates a new NodeBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The parent graph
+    /// * `name` - The name of the node to create
+    ///
+    /// # Returns
+    ///
+    /// A new NodeBuilder instance
+    pub fn new(graph: &'a Graph, name: &str) -> Self {
+        NodeBuilder {
+            graph,
+            name: name.to_owned(),
+            attributes: HashMap::new(),
+            allowed_attributes: None,
+        }
+    }
+    
+    /// Sets an attribute on the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.insert(name.to_owned(), value.to_owned());
+        self
+    }
+    
+    /// Rejects attribute names that are not known at `build()` time.
+    ///
+    /// GraphViz silently ignores misspelled attributes such as `fillcollor`.
+    /// In strict mode `build()` fails instead if an attribute is neither one
+    /// of the constants in `attr::node` nor added with `allow_attribute`.
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn strict_attributes(mut self) -> Self {
+        self.allowed_attributes.get_or_insert_with(HashSet::new);
+        self
+    }
+    
+    /// Accepts an additional attribute name in strict mode.
+    ///
+    /// Use this for valid GraphViz attributes without a constant in
+    /// `attr::node` and for custom attributes. Enables strict mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name to accept
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn allow_attribute(mut self, name: &str) -> Self {
+        self.allowed_attributes.get_or_insert_with(HashSet::new).insert(name.to_owned());
+        self
+    }
+    
+    /// Sets the label of the node.
+    ///
+    /// The value is used as-is, so it may contain GraphViz escapes such as
+    /// those produced by `attr::escape_label` or `attr::multiline_label`.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn label(self, label: &str) -> Self {
+        self.attribute(attr::node::LABEL, label)
+    }
+    
+    /// Sets an external label (`xlabel`), placed near the node after layout
+    /// without changing the node's size.
+    ///
+    /// GraphViz drops external labels that would overlap other elements
+    /// unless `Graph::set_force_labels` is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn external_label(self, label: &str) -> Self {
+        self.attribute(attr::node::XLABEL, label)
+    }
+    
+    /// Sets an image to draw inside the node and how it is scaled.
+    ///
+    /// With `ImageScale::None`, GraphViz's default, the image keeps its natural
+    /// size and the node grows to contain it. The other modes scale the image
+    /// to the node instead, so set the node's `width` and `height` (usually
+    /// together with `fixedsize`) to control the drawn size.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The image file, resolved relative to the graph's `imagepath`
+    /// * `scale` - How the image is scaled to fit the node
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn image(self, path: &str, scale: attr::ImageScale) -> Self {
+        self.attribute(attr::node::IMAGE, path)
+            .attribute(attr::node::IMAGESCALE, scale.as_str())
+    }
+    
+    /// Sets the identifier of the node, emitted as the `id` of its SVG group.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier, which should be unique within the document
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn id(self, id: &str) -> Self {
+        self.attribute(attr::node::ID, id)
+    }
+    
+    /// Sets the class names of the node, emitted in the `class` of its SVG group.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - Space-separated class names
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn class(self, class: &str) -> Self {
+        self.attribute(attr::node::CLASS, class)
+    }
+    
+    /// Makes the node a hyperlink in SVG and image map output.
+    ///
+    /// The URL and tooltip are stored as given. GraphViz escapes them itself
+    /// when writing SVG and image maps, so query strings need no escaping.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The link target
+    /// * `tooltip` - Optional text shown when hovering over the node
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn link(self, url: &str, tooltip: Option<&str>) -> Self {
+        let builder = self.attribute(attr::node::URL, url);
+        
+        match tooltip {
+            Some(tooltip) => builder.attribute(attr::node::TOOLTIP, tooltip),
+            None => builder,
+        }
+    }
+    
+    /// Sets the browser window or frame in which the node's link opens.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target, such as "_blank" or "_top"
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn link_target(self, target: &str) -> Self {
+        self.attribute(attr::node::TARGET, target)
+    }
+    
+    /// Makes the node invisible (`style=invis`) while it keeps its place in the layout.
+    ///
+    /// Invisible nodes and the edges attached to them still take up space,
+    /// which makes them useful as phantom anchors for aligning other nodes.
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn invisible(mut self) -> Self {
+        let style = match self.attributes.get(attr::node::STYLE) {
+            Some(style) if !style.is_empty() => format!("{},{}", style, attr::values::style::INVIS),
+            _ => attr::values::style::INVIS.to_owned(),
+        };
+        self.attributes.insert(attr::node::STYLE.to_owned(), style);
+        self
+    }
+    
+    /// Shapes the node as a polygon with the given number of sides.
+    ///
+    /// # Arguments
+    ///
+    /// * `sides` - The number of sides
+    ///
+    /// # Returns
+    ///
+    /// A PolygonBuilder for configuring the remaining polygon parameters
+    pub fn polygon(self, sides: u32) -> PolygonBuilder<'a> {
+        PolygonBuilder {
+            node: self
+                .attribute(attr::node::SHAPE, attr::values::shape::POLYGON)
+                .attribute(attr::node::SIDES, &sides.to_string()),
+        }
+    }
+    
+    /// Builds and creates the node with the configured attributes.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the new Node or an error
+    pub fn build(self) -> Result<Node<'a>, GraphvizError> {
+        check_attribute_names(&self.attributes, attr::node::ALL, self.allowed_attributes.as_ref())?;
+        
+        let node = self.graph.add_node(&self.name)?;
+        
+        for (name, value) in self.attributes {
+            node.set_attribute(&name, &value)?;
+        }
+        
+        Ok(node)
+    }
+}
+
+// NodeTemplate implementation
+impl NodeTemplate {
+    /// Creates a template without attributes.
+    ///
+    /// # Returns
+    ///
+    /// A new NodeTemplate instance
+    pub fn new() -> Self {
+        Default::default()
+    }
+    
+    /// Sets an attribute applied to every node created from the template.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.insert(name.to_owned(), value.to_owned());
+        self
+    }
+    
+    /// Creates a node with the template's attributes.
+    ///
+    /// If the node already exists, the template's attributes are set on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to add the node to
+    /// * `name` - The name of the node
+    ///
+    /// # Returns
+    ///
+    /// Result containing the new Node or an error
+    pub fn instantiate<'g>(&self, graph: &'g Graph, name: &str) -> Result<Node<'g>, GraphvizError> {
+        let node = graph.add_node(name)?;
+        
+        for (name, value) in &self.attributes {
+            node.set_attribute(name, value)?;
+        }
+        
+        Ok(node)
+    }
+}
+
+// EdgeTemplate implementation
+impl EdgeTemplate {
+    /// Creates a template without attributes.
+    ///
+    /// # Returns
+    ///
+    /// A new EdgeTemplate instance
+    pub fn new() -> Self {
+        Default::default()
+    }
+    
+    /// Sets an attribute applied to every edge created from the template.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.insert(name.to_owned(), value.to_owned());
+        self
+    }
+    
+    /// Creates an edge with the template's attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to add the edge to
+    /// * `from` - The source node
+    /// * `to` - The target node
+    /// * `name` - Optional name for the edge
+    ///
+    /// # Returns
+    ///
+    /// Result containing the new Edge or an error
+    pub fn instantiate<'g>(
+        &self,
+        graph: &'g Graph,
+        from: &Node,
+        to: &Node,
+        name: Option<&str>,
+    ) -> Result<Edge<'g>, GraphvizError> {
+        let edge = graph.add_edge(from, to, name)?;
+        
+        for (name, value) in &self.attributes {
+            edge.set_attribute(name, value)?;
+        }
+        
+        Ok(edge)
+    }
+}
+
+// PolygonBuilder implementation
+impl<'a> PolygonBuilder<'a> {
+    /// Sets the number of outlines drawn around the polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `peripheries` - The number of outlines
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn peripheries(self, peripheries: u32) -> Self {
+        self.attribute(attr::node::PERIPHERIES, &peripheries.to_string())
+    }
+    
+    /// Sets how much the top of the polygon is wider than the bottom.
+    ///
+    /// # Arguments
+    ///
+    /// * `distortion` - The distortion factor (negative values narrow the top)
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn distortion(self, distortion: f64) -> Self {
+        self.attribute(attr::node::DISTORTION, &distortion.to_string())
+    }
+    
+    /// Sets how much the top of the polygon is shifted relative to the bottom.
+    ///
+    /// # Arguments
+    ///
+    /// * `skew` - The skew factor (positive values shift the top to the right)
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn skew(self, skew: f64) -> Self {
+        self.attribute(attr::node::SKEW, &skew.to_string())
+    }
+    
+    /// Sets the rotation of the polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `degrees` - The rotation angle in degrees
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn rotation(self, degrees: f64) -> Self {
+        self.attribute(attr::node::ORIENTATION, &degrees.to_string())
+    }
+    
+    /// Sets any other attribute on the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn attribute(self, name: &str, value: &str) -> Self {
+        PolygonBuilder {
+            node: self.node.attribute(name, value),
+        }
+    }
+    
+    /// Builds and creates the polygon node with the configured attributes.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the new Node or an error
+    pub fn build(self) -> Result<Node<'a>, GraphvizError> {
+        self.node.build()
+    }
+}
+
+// EdgeBuilder implementation
+impl<'a> EdgeBuilder<'a> {
+    /// Creates a new EdgeBuilder.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The parent graph
+    /// * `from` - The source node
+    /// * `to` - The target node
+    /// * `name` - Optional name for the edge
+    ///
+    /// # Returns
+    ///
+    /// A new EdgeBuilder instance
+    pub fn new(graph: &'a Graph, from: &'a Node, to: &'a Node, name: Option<&str>) -> Self {
+        EdgeBuilder {
+            graph,
+            from,
+            to,
+            name: name.map(String::from),
+            attributes: HashMap::new(),
+            allowed_attributes: None,
+        }
+    }
+    
+    /// Sets an attribute on the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    /// * `value` - The attribute value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.insert(name.to_owned(), value.to_owned());
+        self
+    }
+    
+    /// Rejects attribute names that are not known at `build()` time.
+    ///
+    /// GraphViz silently ignores misspelled attributes such as `fillcollor`.
+    /// In strict mode `build()` fails instead if an attribute is neither one
+    /// of the constants in `attr::edge` nor added with `allow_attribute`.
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn strict_attributes(mut self) -> Self {
+        self.allowed_attributes.get_or_insert_with(HashSet::new);
+        self
+    }
+    
+    /// Accepts an additional attribute name in strict mode.
+    ///
+    /// Use this for valid GraphViz attributes without a constant in
+    /// `attr::edge` and for custom attributes. Enables strict mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name to accept
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn allow_attribute(mut self, name: &str) -> Self {
+        self.allowed_attributes.get_or_insert_with(HashSet::new).insert(name.to_owned());
+        self
+    }
+    
+    /// Sets the label of the edge.
+    ///
+    /// The value is used as-is, so it may contain GraphViz escapes such as
+    /// those produced by `attr::escape_label` or `attr::multiline_label`.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn label(self, label: &str) -> Self {
+        self.attribute(attr::edge::LABEL, label)
+    }
+    
+    /// Sets the identifier of the edge, emitted as the `id` of its SVG group.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier, which should be unique within the document
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn id(self, id: &str) -> Self {
+        self.attribute(attr::edge::ID, id)
+    }
+    
+    /// Sets the class names of the edge, emitted in the `class` of its SVG group.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - Space-separated class names
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn class(self, class: &str) -> Self {
+        self.attribute(attr::edge::CLASS, class)
+    }
+    
+    /// Makes the edge a hyperlink in SVG and image map output.
+    ///
+    /// The URL and tooltip are stored as given. GraphViz escapes them itself
+    /// when writing SVG and image maps, so query strings need no escaping.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The link target
+    /// * `tooltip` - Optional text shown when hovering over the edge
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn link(self, url: &str, tooltip: Option<&str>) -> Self {
+        let builder = self.attribute(attr::edge::URL, url);
+        
+        match tooltip {
+            Some(tooltip) => builder.attribute(attr::edge::TOOLTIP, tooltip),
+            None => builder,
+        }
+    }
+    
+    /// Sets the browser window or frame in which the edge's link opens.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target, such as "_blank" or "_top"
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn link_target(self, target: &str) -> Self {
+        self.attribute(attr::edge::TARGET, target)
+    }
+    
+    /// Sets the label drawn near the head (target end) of the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn head_label(self, label: &str) -> Self {
+        self.attribute(attr::edge::HEADLABEL, label)
+    }
+    
+    /// Sets the label drawn near the tail (source end) of the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn tail_label(self, label: &str) -> Self {
+        self.attribute(attr::edge::TAILLABEL, label)
+    }
+    
+    /// Sets an external label (`xlabel`), placed near the edge after layout
+    /// without affecting the layout itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label value
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn external_label(self, label: &str) -> Self {
+        self.attribute(attr::edge::XLABEL, label)
     }
-}
-
-// NodeBuilder implementation
-impl<'a> NodeBuilder<'a> {
-    /// Creates a new NodeBuilder.
+    
+    /// Sets the arrow drawn at the head of the edge.
     ///
     /// # Arguments
     ///
-    /// * `graph` - The parent graph
-    /// * `name` - The name of the node to create
+    /// * `arrow` - The composite arrow
     ///
     /// # Returns
     ///
-    /// A new NodeBuilder instance
-    pub fn new(graph: &'a Graph, name: &str) -> Self {
-        NodeBuilder {
-            graph,
-            name: name.to_owned(),
-            attributes: HashMap::new(),
-        }
+    /// Self for method chaining
+    pub fn arrowhead_type(self, arrow: attr::ArrowType) -> Self {
+        self.attribute(attr::edge::ARROWHEAD, &arrow.to_attribute())
     }
     
-    /// Sets an attribute on the node.
+    /// Sets the arrow drawn at the tail of the edge.
+    ///
+    /// The tail arrow is only drawn when `dir` is `back` or `both`.
     ///
     /// # Arguments
     ///
-    /// * `name` - The attribute name
-    /// * `value` - The attribute value
+    /// * `arrow` - The composite arrow
     ///
     /// # Returns
     ///
     /// Self for method chaining
-    pub fn attribute(mut self, name: &str, value: &str) -> Self {
-        self.attributes.insert(name.to_owned(), value.to_owned());
-        self
+    pub fn arrowtail_type(self, arrow: attr::ArrowType) -> Self {
+        self.attribute(attr::edge::ARROWTAIL, &arrow.to_attribute())
     }
     
-    /// Builds and creates the node with the configured attributes.
+    /// Draws arrowheads at both ends of the edge (`dir=both`).
     ///
     /// # Returns
     ///
-    /// Result containing the new Node or an error
-    pub fn build(self) -> Result<Node<'a>, GraphvizError> {
-        let node = self.graph.add_node(&self.name)?;
+    /// Self for method chaining
+    pub fn bidirectional(self) -> Self {
+        self.attribute(attr::edge::DIR, attr::values::dir::BOTH)
+    }
+    
+    /// Draws the edge without any arrowheads (`dir=none`).
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn no_arrow(self) -> Self {
+        self.attribute(attr::edge::DIR, attr::values::dir::NONE)
+    }
+    
+    /// Sets the weight of the edge.
+    ///
+    /// Heavier edges are kept shorter and straighter. `dot` expects integer weights
+    /// and uses them when ranking nodes, while `neato` and `fdp` treat the weight as
+    /// the strength of the edge's spring. A negative weight makes `build` fail with
+    /// `GraphvizError::InvalidWeight`.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - The non-negative weight
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn weight(self, weight: f64) -> Self {
+        self.attribute(attr::edge::WEIGHT, &weight.to_string())
+    }
+    
+    /// Builds and creates the edge with the configured attributes.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the new Edge or an error
+    pub fn build(self) -> Result<Edge<'a>, GraphvizError> {
+        check_attribute_names(&self.attributes, attr::edge::ALL, self.allowed_attributes.as_ref())?;
+        
+        if let Some(weight) = self.attributes.get(attr::edge::WEIGHT) {
+            parse_weight(weight)?;
+        }
+        
+        let name_ref = self.name.as_deref();
+        let edge = self.graph.add_edge(self.from, self.to, name_ref)?;
         
         for (name, value) in self.attributes {
-            node.set_attribute(&name, &value)?;
+            edge.set_attribute(&name, &value)?;
         }
         
-        Ok(node)
+        Ok(edge)
     }
 }
 
-// EdgeBuilder implementation
-impl<'a> EdgeBuilder<'a> {
-    /// Creates a new EdgeBuilder.
+// AlignmentGroup implementation
+impl<'a> AlignmentGroup<'a> {
+    /// Creates an empty alignment group.
     ///
     /// # Arguments
     ///
-    /// * `graph` - The parent graph
-    /// * `from` - The source node
-    /// * `to` - The target node
-    /// * `name` - Optional name for the edge
+    /// * `graph` - The graph containing the nodes
     ///
     /// # Returns
     ///
-    /// A new EdgeBuilder instance
-    pub fn new(graph: &'a Graph, from: &'a Node, to: &'a Node, name: Option<&str>) -> Self {
-        EdgeBuilder {
+    /// A new AlignmentGroup
+    pub fn new(graph: &'a Graph) -> Self {
+        AlignmentGroup {
             graph,
-            from,
-            to,
-            name: name.map(String::from),
-            attributes: HashMap::new(),
+            nodes: Vec::new(),
+            ordered: false,
         }
     }
     
-    /// Sets an attribute on the edge.
+    /// Adds a node to the group.
     ///
     /// # Arguments
     ///
-    /// * `name` - The attribute name
-    /// * `value` - The attribute value
+    /// * `node` - A node of the group's graph
     ///
     /// # Returns
     ///
     /// Self for method chaining
-    pub fn attribute(mut self, name: &str, value: &str) -> Self {
-        self.attributes.insert(name.to_owned(), value.to_owned());
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, node: &Node) -> Self {
+        self.nodes.push(node.inner);
         self
     }
     
-    /// Builds and creates the edge with the configured attributes.
+    /// Keeps the nodes in the order they were added, left to right in a
+    /// top-to-bottom layout.
+    ///
+    /// Consecutive nodes are joined by invisible edges with
+    /// `constraint=false`, which order nodes within a rank without affecting
+    /// how ranks are assigned.
     ///
     /// # Returns
     ///
-    /// Result containing the new Edge or an error
-    pub fn build(self) -> Result<Edge<'a>, GraphvizError> {
-        let name_ref = self.name.as_deref();
-        let edge = self.graph.add_edge(self.from, self.to, name_ref)?;
+    /// Self for method chaining
+    pub fn ordered(mut self) -> Self {
+        self.ordered = true;
+        self
+    }
+    
+    /// Places all nodes of the group on the same rank.
+    ///
+    /// This creates an anonymous subgraph with `rank=same` holding the nodes.
+    /// Rank constraints are honored by the dot engine only.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the created Subgraph or an error
+    pub fn same_rank(self) -> Result<Subgraph<'a>, GraphvizError> {
+        self.rank(attr::values::rank::SAME)
+    }
+    
+    /// Constrains the rank of all nodes of the group.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The `rank` value, e.g. `attr::values::rank::MIN`
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the created Subgraph or an error, including
+    /// `GraphvizError::ForeignNode` if a node belongs to another graph, in
+    /// which case nothing is created
+    pub fn rank(self, rank: &str) -> Result<Subgraph<'a>, GraphvizError> {
+        for &node in &self.nodes {
+            self.graph.check_same_root(&Node { inner: node, _phantom: PhantomData })?;
+        }
         
-        for (name, value) in self.attributes {
-            edge.set_attribute(&name, &value)?;
+        let inner = unsafe { sys::agsubg(self.graph.inner, ptr::null_mut(), 1) };
+        
+        if inner.is_null() {
+            return Err(GraphvizError::GraphCreationFailed);
         }
         
-        Ok(edge)
+        let subgraph = Subgraph::from_raw(inner);
+        subgraph.set_attribute(attr::graph::RANK, rank)?;
+        
+        for &node in &self.nodes {
+            if unsafe { sys::agsubnode(inner, node, 1) }.is_null() {
+                return Err(GraphvizError::NodeCreationFailed);
+            }
+        }
+        
+        if self.ordered {
+            for pair in self.nodes.windows(2) {
+                let tail = Node { inner: pair[0], _phantom: PhantomData };
+                let head = Node { inner: pair[1], _phantom: PhantomData };
+                
+                self.graph.create_edge(&tail, &head, None)
+                    .attribute(attr::edge::STYLE, attr::values::style::INVIS)
+                    .attribute(attr::edge::CONSTRAINT, "false")
+                    .build()?;
+            }
+        }
+        
+        Ok(subgraph)
     }
 }
 
@@ -791,11 +3393,118 @@ impl GraphBuilder {
 impl Drop for Graph {
     fn drop(&mut self) {
         if self.owned && !self.inner.is_null() {
+            // Release an active layout while its context is still alive
+            if let Some(context) = self.layout_context.take() {
+                layout::serialized(|| unsafe { sys::gvFreeLayout(context.inner, self.inner) });
+            }
+            
             unsafe { sys::agclose(self.inner) };
         }
     }
 }
 
+// Subgraph implementation
+impl<'a> Subgraph<'a> {
+    /// Wraps a subgraph pointer owned by its root graph.
+    pub(crate) fn from_raw(inner: *mut sys::Agraph_t) -> Self {
+        Subgraph {
+            graph: Graph { inner, owned: false, layout_context: None },
+            _phantom: PhantomData,
+        }
+    }
+    
+    /// Checks whether the subgraph is a cluster, i.e. its name starts with `cluster`.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing true if dot draws the subgraph as a cluster
+    pub fn is_cluster(&self) -> Result<bool, GraphvizError> {
+        Ok(self.graph.name()?.starts_with("cluster"))
+    }
+    
+    /// Gets the root graph containing this subgraph.
+    ///
+    /// # Returns
+    ///
+    /// A GraphRef borrowing the root graph for the subgraph's lifetime
+    pub fn root(&self) -> GraphRef<'a> {
+        let root = unsafe { sys::agroot(self.graph.inner as *mut _) };
+        
+        GraphRef {
+            graph: Graph { inner: root, owned: false, layout_context: None },
+            _phantom: PhantomData,
+        }
+    }
+    
+    /// Gets how deeply the subgraph is nested below the root graph.
+    ///
+    /// The root graph itself is at depth 0, so a direct subgraph of the root
+    /// is at depth 1.
+    ///
+    /// # Returns
+    ///
+    /// The number of parent links from the subgraph up to the root
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut current = self.graph.inner;
+        
+        loop {
+            let parent = unsafe { sys::agparent(current) };
+            
+            if parent.is_null() || parent == current {
+                return depth;
+            }
+            
+            depth += 1;
+            current = parent;
+        }
+    }
+    
+    /// Copies the subgraph into a new, independent root graph.
+    ///
+    /// The copy holds the subgraph's nodes and edges with their attributes and
+    /// takes the subgraph's graph attributes, such as a cluster's `label`, as
+    /// its own. Nested subgraphs are recreated with their node membership, so
+    /// nested clusters are still drawn; their edges belong to the root only.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Graph or an error
+    pub fn to_graph(&self) -> Result<Graph, GraphvizError> {
+        let copy = self.graph.empty_copy(self.graph.is_strict())?;
+        unsafe { copy_attribute_values(sys::AGRAPH, self.graph.inner as *mut _, copy.inner as *mut _)? };
+        
+        for node in self.graph.nodes() {
+            copy.import_node(&node)?;
+        }
+        
+        for node in self.graph.nodes() {
+            for edge in self.graph.out_edges(&node) {
+                copy.import_edge(&edge, &edge.tail().name()?, &edge.head().name()?)?;
+            }
+        }
+        
+        copy_nested_subgraphs(&self.graph, &copy)?;
+        Ok(copy)
+    }
+}
+
+impl<'a> Deref for Subgraph<'a> {
+    type Target = Graph;
+    
+    fn deref(&self) -> &Graph {
+        &self.graph
+    }
+}
+
+impl<'a> Deref for GraphRef<'a> {
+    type Target = Graph;
+    
+    fn deref(&self) -> &Graph {
+        &self.graph
+    }
+}
+
 // Node implementation
 impl<'a> Node<'a> {
     /// Gets the name of the node.
@@ -818,23 +3527,176 @@ impl<'a> Node<'a> {
         Ok(name_str)
     }
     
+    /// Gets the underlying `Agnode_t` pointer for use with `graphviz_sys`.
+    ///
+    /// The pointer is valid as long as the node exists in its graph; deleting
+    /// it through the raw pointer invalidates this Node.
+    ///
+    /// # Returns
+    ///
+    /// The raw node pointer
+    pub fn as_raw(&self) -> *mut sys::Agnode_t {
+        self.inner
+    }
+    
     /// Gets the parent graph of this node.
     ///
     /// # Returns
     ///
-    /// The parent graph as an unowned Graph reference
-    pub fn graph(&self) -> Graph {
+    /// A GraphRef borrowing the parent graph for the node's lifetime
+    pub fn graph(&self) -> GraphRef<'a> {
         let graph_ptr = unsafe { sys::agraphof(self.inner as *mut _) };
         
-        Graph {
-            inner: graph_ptr,
-            owned: false,  // We don't own this graph, just referencing it
+        GraphRef {
+            graph: Graph {
+                inner: graph_ptr,
+                owned: false,  // We don't own this graph, just referencing it
+                layout_context: None,
+            },
+            _phantom: PhantomData,
+        }
+    }
+    
+    /// Checks whether an attribute is set on this node rather than inherited.
+    ///
+    /// `get_attribute` reports the effective value, which falls back to the
+    /// graph's node default. This compares the node's value with that default
+    /// instead, so a value explicitly set to the default counts as inherited.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    ///
+    /// # Returns
+    ///
+    /// A Result containing true if the node's value differs from the default,
+    /// or false if it is inherited or the attribute is not declared
+    pub fn attribute_is_local(&self, name: &str) -> Result<bool, GraphvizError> {
+        let name = error::c_string(name, "attribute name")?;
+        
+        let sym = unsafe {
+            let root = sys::agroot(self.inner as *mut _);
+            sys::agattr(root, sys::AGNODE as i32, name.as_ptr() as *mut _, ptr::null_mut())
+        };
+        
+        if sym.is_null() {
+            return Ok(false);
+        }
+        
+        let value = unsafe { sys::agxget(self.inner as *mut _, sym) };
+        
+        if value.is_null() {
+            return Err(GraphvizError::AttributeGetFailed);
         }
+        
+        Ok(unsafe { CStr::from_ptr(value) != CStr::from_ptr((*sym).defval) })
+    }
+    
+    /// Shows or hides the node by adding or removing `invis` from its style.
+    ///
+    /// Other style values such as `filled` are kept in their original order
+    /// (see `AttributeContainer::toggle_style`). A hidden node still
+    /// takes up space in the layout, as do the edges attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `visible` - Whether the node should be drawn
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_visible(&self, visible: bool) -> Result<(), GraphvizError> {
+        self.toggle_style(attr::values::style::INVIS, !visible)
+    }
+    
+    /// Finds the innermost subgraph of `graph` containing this node.
+    ///
+    /// If the node belongs to several sibling subgraphs, the first one in
+    /// iteration order wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph whose subgraphs are searched
+    ///
+    /// # Returns
+    ///
+    /// The innermost subgraph containing the node, or None if it belongs to
+    /// no subgraph
+    pub fn parent_subgraph<'g>(&self, graph: &'g Graph) -> Option<Subgraph<'g>> {
+        innermost_subgraph(graph.inner, self.inner, false).map(Subgraph::from_raw)
+    }
+    
+    /// Keeps the edges around this node in creation order during layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `ordering` - Whether incoming or outgoing edges keep their order
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn set_ordering(&self, ordering: attr::Ordering) -> Result<(), GraphvizError> {
+        self.set_attribute(attr::node::ORDERING, ordering.as_str())
+    }
+    
+    /// Collects the distinct nodes reached by following this node's outgoing edges.
+    ///
+    /// Parallel edges contribute their target only once, and a self-loop
+    /// includes this node in the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph whose edges are followed
+    ///
+    /// # Returns
+    ///
+    /// The successor nodes in edge order
+    pub fn successors<'g>(&self, graph: &'g Graph) -> Vec<Node<'g>> {
+        let targets = graph.out_edges(self).map(|edge| edge.head().inner);
+        distinct_nodes(targets)
+    }
+    
+    /// Collects the distinct nodes reached by following this node's incoming edges backwards.
+    ///
+    /// Parallel edges contribute their source only once, and a self-loop
+    /// includes this node in the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph whose edges are followed
+    ///
+    /// # Returns
+    ///
+    /// The predecessor nodes in edge order
+    pub fn predecessors<'g>(&self, graph: &'g Graph) -> Vec<Node<'g>> {
+        let sources = graph.in_edges(self).map(|edge| edge.tail().inner);
+        distinct_nodes(sources)
+    }
+    
+    /// Gets the attributes set on this node that differ from their defaults.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the attribute name/value pairs or an error
+    pub(crate) fn local_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        unsafe { local_attributes(sys::AGNODE, self.inner as *mut _) }
     }
 }
 
 // Edge implementation
 impl<'a> Edge<'a> {
+    /// Gets the underlying `Agedge_t` pointer for use with `graphviz_sys`.
+    ///
+    /// The pointer is valid as long as the edge exists in its graph; deleting
+    /// it through the raw pointer invalidates this Edge.
+    ///
+    /// # Returns
+    ///
+    /// The raw edge pointer
+    pub fn as_raw(&self) -> *mut sys::Agedge_t {
+        self.inner
+    }
+    
     /// Retrieves the source node (tail) of this edge.
     ///
     /// # Returns
@@ -909,6 +3771,19 @@ impl<'a> Edge<'a> {
         }
     }
     
+    /// Gets the weight of the edge.
+    ///
+    /// # Returns
+    ///
+    /// Option containing the weight if one is set, or `GraphvizError::InvalidWeight`
+    /// if the attribute is not a non-negative number
+    pub fn weight(&self) -> Result<Option<f64>, GraphvizError> {
+        match self.get_attribute(attr::edge::WEIGHT)? {
+            Some(weight) if !weight.is_empty() => parse_weight(&weight).map(Some),
+            _ => Ok(None),
+        }
+    }
+    
     // Auxiliary methods for node determination
     
     /// Determines source node through graph traversal for directed edges.
@@ -969,6 +3844,568 @@ impl<'a> Edge<'a> {
         // Default behavior if opposite node cannot be determined
         known_node
     }
+    
+    /// Retrieves the target node (head) of this edge.
+    ///
+    /// # Returns
+    ///
+    /// The target node of the edge.
+    pub fn to_node(&self) -> Node<'a> {
+        self.head()
+    }
+    
+    /// Retrieves the endpoint of this edge opposite to the given node.
+    ///
+    /// For a self-loop the node itself is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - One endpoint of the edge
+    ///
+    /// # Returns
+    ///
+    /// The other endpoint, or None if `node` is not an endpoint of this edge
+    pub fn other_endpoint(&self, node: &Node) -> Option<Node<'a>> {
+        let tail = self.tail();
+        let head = self.head();
+        
+        if tail.inner == node.inner {
+            Some(head)
+        } else if head.inner == node.inner {
+            Some(tail)
+        } else {
+            None
+        }
+    }
+    
+    /// Checks whether this edge starts and ends at the same node.
+    ///
+    /// # Returns
+    ///
+    /// true if the tail and head are the same node
+    pub fn is_self_loop(&self) -> bool {
+        self.tail().inner == self.head().inner
+    }
+    
+    /// Checks whether this edge connects two nodes.
+    ///
+    /// In a directed graph the edge must run from `a` to `b`; in an undirected
+    /// graph either orientation matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first node (the tail, for directed graphs)
+    /// * `b` - The second node (the head, for directed graphs)
+    ///
+    /// # Returns
+    ///
+    /// true if the edge joins `a` and `b`
+    pub fn connects(&self, a: &Node, b: &Node) -> bool {
+        let tail = self.tail().inner;
+        let head = self.head().inner;
+        let directed = unsafe { sys::agisdirected(sys::agraphof(self.inner as *mut _)) != 0 };
+        
+        (tail == a.inner && head == b.inner)
+            || (!directed && tail == b.inner && head == a.inner)
+    }
+    
+    /// Gets the outgoing half of the edge pair.
+    ///
+    /// cgraph stores every edge as an out/in pair; this mirrors the `AGMKOUT` macro.
+    pub(crate) fn out_ptr(&self) -> *mut sys::Agedge_t {
+        unsafe {
+            if (*self.inner).base.tag.objtype() == sys::AGOUTEDGE {
+                self.inner
+            } else {
+                self.inner.sub(1)
+            }
+        }
+    }
+    
+    /// Gets the incoming half of the edge pair, mirroring the `AGMKIN` macro.
+    pub(crate) fn in_ptr(&self) -> *mut sys::Agedge_t {
+        unsafe {
+            if (*self.inner).base.tag.objtype() == sys::AGINEDGE {
+                self.inner
+            } else {
+                self.inner.add(1)
+            }
+        }
+    }
+    
+    /// Gets the tail (source) node of the edge, mirroring the `agtail` macro.
+    pub(crate) fn tail(&self) -> Node<'a> {
+        Node {
+            inner: unsafe { (*self.in_ptr()).node },
+            _phantom: PhantomData,
+        }
+    }
+    
+    /// Gets the head (target) node of the edge, mirroring the `aghead` macro.
+    pub(crate) fn head(&self) -> Node<'a> {
+        Node {
+            inner: unsafe { (*self.out_ptr()).node },
+            _phantom: PhantomData,
+        }
+    }
+    
+    /// Gets the name (key) of the edge, if it was given one.
+    ///
+    /// # Returns
+    ///
+    /// Option containing the edge name, or None for anonymous edges
+    pub(crate) fn key(&self) -> Result<Option<String>, GraphvizError> {
+        let name = unsafe { sys::agnameof(self.inner as *mut _) };
+        
+        if name.is_null() {
+            return Ok(None);
+        }
+        
+        let c_str = unsafe { CStr::from_ptr(name) };
+        let name_str = c_str.to_str()
+            .map_err(|_| GraphvizError::InvalidUtf8)?
+            .to_owned();
+        
+        Ok(Some(name_str))
+    }
+    
+    /// Gets the attributes set on this edge that differ from their defaults.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the attribute name/value pairs or an error
+    pub(crate) fn local_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        unsafe { local_attributes(sys::AGEDGE, self.inner as *mut _) }
+    }
+}
+
+// ValidationWarning implementation
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationWarning::EmptyGraph => write!(f, "Graph has no nodes"),
+            ValidationWarning::InvalidAttribute { target, name, value } => {
+                write!(f, "Invalid value {:?} for attribute {} on {}", value, name, target)
+            }
+        }
+    }
+}
+
+/// Formats a name for a warning message, as DOT if possible and as a Rust string literal otherwise.
+fn display_id(name: &str) -> String {
+    attr::quote_id(name).unwrap_or_else(|_| format!("{:?}", name))
+}
+
+/// Records a warning for each numeric attribute that is negative or not a number.
+fn check_attributes(
+    target: String,
+    attributes: Result<Vec<(String, String)>, GraphvizError>,
+    warnings: &mut Vec<ValidationWarning>,
+) {
+    for (name, value) in attributes.unwrap_or_default() {
+        if !NON_NEGATIVE_ATTRIBUTES.contains(&name.as_str()) {
+            continue;
+        }
+        
+        let valid = matches!(value.trim().parse::<f64>(), Ok(number) if number >= 0.0);
+        
+        if !valid {
+            warnings.push(ValidationWarning::InvalidAttribute {
+                target: target.clone(),
+                name,
+                value,
+            });
+        }
+    }
+}
+
+/// A node waiting in the Dijkstra queue with the cost of reaching it.
+struct PathCandidate {
+    /// Total cost of the best known path to the node
+    cost: f64,
+    /// The node reached
+    node: *mut sys::Agnode_t,
+}
+
+// PathCandidate ordering, reversed so the cheapest candidate is popped first
+impl PartialEq for PathCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PathCandidate {}
+
+impl PartialOrd for PathCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PathCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// Recreates the subgraphs of `source` in `target`, with their attributes and nodes.
+///
+/// The nodes must already exist in `target`'s root graph.
+fn copy_nested_subgraphs(source: &Graph, target: &Graph) -> Result<(), GraphvizError> {
+    for subgraph in source.subgraphs() {
+        let nested = target.add_subgraph(&subgraph.name()?)?;
+        unsafe { copy_attribute_values(sys::AGRAPH, subgraph.inner as *mut _, nested.inner as *mut _)? };
+        
+        for node in subgraph.nodes() {
+            nested.add_node(&node.name()?)?;
+        }
+        
+        copy_nested_subgraphs(&subgraph, &nested)?;
+    }
+    
+    Ok(())
+}
+
+/// Collects every subgraph of `graph`, at any depth, for which `contains` returns true.
+fn subgraphs_containing<F>(graph: *mut sys::Agraph_t, contains: F) -> Vec<*mut sys::Agraph_t>
+where
+    F: Fn(*mut sys::Agraph_t) -> bool + Copy,
+{
+    let mut found = Vec::new();
+    let mut subgraph = unsafe { sys::agfstsubg(graph) };
+    
+    while !subgraph.is_null() {
+        if contains(subgraph) {
+            found.push(subgraph);
+            found.extend(subgraphs_containing(subgraph, contains));
+        }
+        
+        subgraph = unsafe { sys::agnxtsubg(subgraph) };
+    }
+    
+    found
+}
+
+/// Searches the subgraphs of `graph` depth-first for the deepest one containing `node`.
+fn innermost_subgraph(
+    graph: *mut sys::Agraph_t,
+    node: *mut sys::Agnode_t,
+    clusters_only: bool,
+) -> Option<*mut sys::Agraph_t> {
+    let mut subgraph = unsafe { sys::agfstsubg(graph) };
+    
+    while !subgraph.is_null() {
+        if !unsafe { sys::agsubnode(subgraph, node, 0) }.is_null() {
+            if let Some(inner) = innermost_subgraph(subgraph, node, clusters_only) {
+                return Some(inner);
+            }
+            
+            if !clusters_only || Subgraph::from_raw(subgraph).is_cluster().unwrap_or(false) {
+                return Some(subgraph);
+            }
+        }
+        
+        subgraph = unsafe { sys::agnxtsubg(subgraph) };
+    }
+    
+    None
+}
+
+/// Wraps node pointers as nodes, keeping only the first occurrence of each.
+fn distinct_nodes<'g, I: Iterator<Item = *mut sys::Agnode_t>>(pointers: I) -> Vec<Node<'g>> {
+    let mut seen = HashSet::new();
+    
+    pointers
+        .filter(|inner| seen.insert(*inner))
+        .map(|inner| Node { inner, _phantom: PhantomData })
+        .collect()
+}
+
+/// Computes graph density from the node count and the number of non-loop edges.
+fn density_of(nodes: usize, edges: usize, directed: bool) -> f64 {
+    if nodes < 2 {
+        return 0.0;
+    }
+    
+    let possible = (nodes * (nodes - 1)) as f64;
+    
+    if directed {
+        edges as f64 / possible
+    } else {
+        2.0 * edges as f64 / possible
+    }
+}
+
+/// Counts connected components with union-find, ignoring edge direction.
+fn count_components(nodes: usize, edges: &[(usize, usize)]) -> usize {
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        
+        i
+    }
+    
+    let mut parent: Vec<usize> = (0..nodes).collect();
+    let mut components = nodes;
+    
+    for &(tail, head) in edges {
+        let (a, b) = (find(&mut parent, tail), find(&mut parent, head));
+        
+        if a != b {
+            parent[a] = b;
+            components -= 1;
+        }
+    }
+    
+    components
+}
+
+/// Checks whether directed edges form no cycle using Kahn's algorithm.
+fn is_dag(nodes: usize, edges: &[(usize, usize)]) -> bool {
+    let mut successors = vec![Vec::new(); nodes];
+    let mut in_degree = vec![0usize; nodes];
+    
+    for &(tail, head) in edges {
+        successors[tail].push(head);
+        in_degree[head] += 1;
+    }
+    
+    let mut ready: Vec<usize> = (0..nodes).filter(|&i| in_degree[i] == 0).collect();
+    let mut removed = 0;
+    
+    while let Some(node) = ready.pop() {
+        removed += 1;
+        
+        for &next in &successors[node] {
+            in_degree[next] -= 1;
+            
+            if in_degree[next] == 0 {
+                ready.push(next);
+            }
+        }
+    }
+    
+    removed == nodes
+}
+
+/// Builds successor lists from (tail, head) index pairs, following undirected edges both ways.
+fn successor_lists(nodes: usize, edges: &[(usize, usize)], directed: bool) -> Vec<Vec<usize>> {
+    let mut successors = vec![Vec::new(); nodes];
+    
+    for &(tail, head) in edges {
+        successors[tail].push(head);
+        
+        if !directed && tail != head {
+            successors[head].push(tail);
+        }
+    }
+    
+    successors
+}
+
+/// Marks the nodes reachable from `start` by a path of at least one edge.
+fn reachable_from(successors: &[Vec<usize>], start: usize) -> Vec<bool> {
+    let mut reached = vec![false; successors.len()];
+    let mut stack = successors[start].clone();
+    
+    while let Some(node) = stack.pop() {
+        if !reached[node] {
+            reached[node] = true;
+            stack.extend(&successors[node]);
+        }
+    }
+    
+    reached
+}
+
+/// Checks builder attribute names against the known names when strict checking is enabled.
+///
+/// Reports the alphabetically first unknown name so the error is deterministic.
+fn check_attribute_names(
+    attributes: &HashMap<String, String>,
+    known: &[&str],
+    allowed: Option<&HashSet<String>>,
+) -> Result<(), GraphvizError> {
+    let Some(allowed) = allowed else {
+        return Ok(());
+    };
+    
+    let unknown = attributes.keys()
+        .filter(|name| !known.contains(&name.as_str()) && !allowed.contains(*name))
+        .min();
+    
+    match unknown {
+        Some(name) => Err(GraphvizError::UnknownAttribute(name.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Tallies a non-empty attribute value.
+fn count_value(counts: &mut HashMap<String, usize>, value: Option<String>) {
+    if let Some(value) = value.filter(|value| !value.is_empty()) {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+}
+
+/// Parses an edge weight, rejecting negative and non-numeric values.
+fn parse_weight(value: &str) -> Result<f64, GraphvizError> {
+    match value.trim().parse::<f64>() {
+        Ok(weight) if weight >= 0.0 => Ok(weight),
+        _ => Err(GraphvizError::InvalidWeight),
+    }
+}
+
+// Attribute copying helpers
+
+/// Declares every attribute of the given kind from `source` on `target` with the same default.
+unsafe fn copy_attribute_declarations(
+    source: *mut sys::Agraph_t,
+    target: *mut sys::Agraph_t,
+    kind: u32,
+) -> Result<(), GraphvizError> {
+    let mut sym = sys::agnxtattr(source, kind as i32, ptr::null_mut());
+    
+    while !sym.is_null() {
+        let declared = sys::agattr(target, kind as i32, (*sym).name, (*sym).defval);
+        
+        if declared.is_null() {
+            return Err(GraphvizError::AttributeSetFailed);
+        }
+        
+        sym = sys::agnxtattr(source, kind as i32, sym);
+    }
+    
+    Ok(())
+}
+
+/// Collects the attribute values of an object that differ from their declared defaults.
+unsafe fn local_attributes(
+    kind: u32,
+    obj: *mut std::os::raw::c_void,
+) -> Result<Vec<(String, String)>, GraphvizError> {
+    let root = sys::agroot(obj);
+    let mut attributes = Vec::new();
+    let mut sym = sys::agnxtattr(root, kind as i32, ptr::null_mut());
+    
+    while !sym.is_null() {
+        let value = sys::agxget(obj, sym);
+        
+        if !value.is_null() {
+            let value = CStr::from_ptr(value);
+            
+            if value != CStr::from_ptr((*sym).defval) {
+                let name = CStr::from_ptr((*sym).name).to_str()?.to_owned();
+                attributes.push((name, value.to_str()?.to_owned()));
+            }
+        }
+        
+        sym = sys::agnxtattr(root, kind as i32, sym);
+    }
+    
+    Ok(attributes)
+}
+
+/// Gets every attribute of the given kind declared on a graph with a non-empty default.
+unsafe fn attribute_defaults(
+    graph: *mut sys::Agraph_t,
+    kind: u32,
+) -> Result<Vec<(String, String)>, GraphvizError> {
+    let mut defaults = Vec::new();
+    let mut sym = sys::agnxtattr(graph, kind as i32, ptr::null_mut());
+    
+    while !sym.is_null() {
+        let default = CStr::from_ptr((*sym).defval).to_str()?;
+        
+        if !default.is_empty() {
+            let name = CStr::from_ptr((*sym).name).to_str()?;
+            defaults.push((name.to_owned(), default.to_owned()));
+        }
+        
+        sym = sys::agnxtattr(graph, kind as i32, sym);
+    }
+    
+    Ok(defaults)
+}
+
+/// Sets already-declared attribute values on an object.
+unsafe fn set_attribute_values(
+    obj: *mut std::os::raw::c_void,
+    attributes: &[(String, String)],
+) -> Result<(), GraphvizError> {
+    for (name, value) in attributes {
+        let name = error::c_string(name.as_str(), "attribute name")?;
+        let value = error::c_string(value.as_str(), "attribute value")?;
+        
+        if sys::agset(obj, name.as_ptr() as *mut _, value.as_ptr() as *mut _) != 0 {
+            return Err(GraphvizError::AttributeSetFailed);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Copies every attribute value of `source` that differs from its declared default onto `target`.
+///
+/// The attributes must already be declared on the graph owning `target`.
+unsafe fn copy_attribute_values(
+    kind: u32,
+    source: *mut std::os::raw::c_void,
+    target: *mut std::os::raw::c_void,
+) -> Result<(), GraphvizError> {
+    let root = sys::agroot(source);
+    let mut sym = sys::agnxtattr(root, kind as i32, ptr::null_mut());
+    
+    while !sym.is_null() {
+        let value = sys::agxget(source, sym);
+        let differs = !value.is_null() && CStr::from_ptr(value) != CStr::from_ptr((*sym).defval);
+        
+        if differs && sys::agset(target, (*sym).name, value) != 0 {
+            return Err(GraphvizError::AttributeSetFailed);
+        }
+        
+        sym = sys::agnxtattr(root, kind as i32, sym);
+    }
+    
+    Ok(())
+}
+
+// DOT parsing helpers
+
+/// The I/O discipline used by `Graph::from_dot_reader`, reading from a `ReadChannel`.
+static READ_DISC: OnceLock<sys::Agiodisc_t> = OnceLock::new();
+
+/// A reader passed to GraphViz's parser as its input channel.
+struct ReadChannel<'r> {
+    /// The source of DOT text
+    reader: &'r mut dyn Read,
+    /// The first error returned by the reader, which ends the input
+    error: Option<io::Error>,
+}
+
+/// Fills GraphViz's parser buffer from a `ReadChannel`.
+///
+/// Returns the number of bytes read, or 0 at the end of input or after an error.
+unsafe extern "C" fn read_channel(chan: *mut c_void, buf: *mut c_char, bufsize: c_int) -> c_int {
+    let channel = &mut *(chan as *mut ReadChannel);
+    
+    if channel.error.is_some() || buf.is_null() || bufsize <= 0 {
+        return 0;
+    }
+    
+    let buffer = slice::from_raw_parts_mut(buf as *mut u8, bufsize as usize);
+    
+    loop {
+        match channel.reader.read(buffer) {
+            Ok(read) => return read as c_int,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => {
+                channel.error = Some(err);
+                return 0;
+            }
+        }
+    }
 }
 
 // AttributeContainer implementations for Graph, Node, and Edge
@@ -980,13 +4417,17 @@ impl AttributeContainer for Graph {
     fn get_attribute(&self, name: &str) -> Result<Option<String>, GraphvizError> {
         self.get_attribute(name)
     }
+    
+    fn local_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        self.graph_attributes()
+    }
 }
 
 impl<'a> AttributeContainer for Node<'a> {
     fn set_attribute(&self, name: &str, value: &str) -> Result<(), GraphvizError> {
         let graph = unsafe { sys::agraphof(self.inner as *mut _) };
-        let name_cstr = CString::new(name)?;
-        let value_cstr = CString::new(value)?;
+        let name_cstr = error::c_string(name, "attribute name")?;
+        let value_cstr = error::c_string(value, "attribute value")?;
         let empty_str = CString::new("")?;
         
         // First create/get the attribute with empty string as default
@@ -1017,7 +4458,7 @@ impl<'a> AttributeContainer for Node<'a> {
     }
     
     fn get_attribute(&self, name: &str) -> Result<Option<String>, GraphvizError> {
-        let name = CString::new(name)?;
+        let name = error::c_string(name, "attribute name")?;
         
         let value = unsafe { sys::agget(self.inner as *mut _, name.as_ptr() as *mut _) };
         
@@ -1032,13 +4473,17 @@ impl<'a> AttributeContainer for Node<'a> {
         
         Ok(Some(value_str))
     }
+    
+    fn local_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        self.local_attributes()
+    }
 }
 
 impl<'a> AttributeContainer for Edge<'a> {
     fn set_attribute(&self, name: &str, value: &str) -> Result<(), GraphvizError> {
         let graph = unsafe { sys::agraphof(self.inner as *mut _) };
-        let name_cstr = CString::new(name)?;
-        let value_cstr = CString::new(value)?;
+        let name_cstr = error::c_string(name, "attribute name")?;
+        let value_cstr = error::c_string(value, "attribute value")?;
         let empty_str = CString::new("")?;
         
         // First create/get the attribute with empty string as default
@@ -1069,7 +4514,7 @@ impl<'a> AttributeContainer for Edge<'a> {
     }
     
     fn get_attribute(&self, name: &str) -> Result<Option<String>, GraphvizError> {
-        let name = CString::new(name)?;
+        let name = error::c_string(name, "attribute name")?;
         
         let value = unsafe { sys::agget(self.inner as *mut _, name.as_ptr() as *mut _) };
         
@@ -1084,4 +4529,8 @@ impl<'a> AttributeContainer for Edge<'a> {
         
         Ok(Some(value_str))
     }
+    
+    fn local_attributes(&self) -> Result<Vec<(String, String)>, GraphvizError> {
+        self.local_attributes()
+    }
 }
@@ -3,15 +3,25 @@
 //! This module provides functions for applying layouts to graphs using
 //! various GraphViz layout engines.
 
-use std::ffi::CString;
+use std::cell::Cell;
+use std::env;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::Path;
 use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
 
 use graphviz_sys as sys;
+use crate::attr::{AttributeContainer, Splines};
 use crate::error::GraphvizError;
-use crate::graph::Graph;
+use crate::graph::{Graph, Node};
+use crate::render::{self, Format};
 
 /// A GraphViz layout engine.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Engine {
     /// Hierarchical layout (default).
     Dot,
@@ -72,29 +82,112 @@ impl Engine {
 }
 
 /// A GraphViz context for layout and rendering operations.
+///
+/// A graph laid out with a context keeps the underlying GraphViz context
+/// alive until its layout is freed, either by `free_layout` or when the graph
+/// is dropped. Contexts and graphs can therefore be dropped in any order.
 pub struct Context {
     /// Pointer to the underlying GVC_t structure
     pub(crate) inner: *mut sys::GVC_t,
+    /// Shared ownership of `inner`, also held by graphs with an active layout
+    handle: Arc<ContextHandle>,
+    /// Seed applied to graphs laid out without their own `start` seed
+    seed: Cell<Option<u32>>,
+}
+
+/// Owner of a GVC_t, freed once the context and every graph laid out with it are gone.
+pub(crate) struct ContextHandle {
+    /// Pointer to the underlying GVC_t structure
+    pub(crate) inner: *mut sys::GVC_t,
+}
+
+// The handle is never used through a shared reference; it only frees the
+// context, on whichever thread drops the last reference.
+unsafe impl Send for ContextHandle {}
+unsafe impl Sync for ContextHandle {}
+
+/// The oldest GraphViz version whose ABI matches these bindings.
+///
+/// GraphViz 3.0.0 changed the length argument of `gvRenderData` from
+/// `unsigned int *` to `size_t *`. The bindings pass a `size_t` pointer, so an
+/// older library would write only part of it and report garbage lengths.
+const MIN_GRAPHVIZ_VERSION: (u32, u32, u32) = (3, 0, 0);
+
+/// Version and plugin details of the GraphViz library linked at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// The GraphViz version, e.g. "9.0.0"
+    pub version: String,
+    /// The date GraphViz was built
+    pub build_date: String,
+    /// Layout engines provided by the loaded plugins
+    pub layout_engines: Vec<String>,
+    /// Output formats provided by the loaded plugins
+    pub formats: Vec<String>,
+}
+
+/// The routed path of an edge, as computed by a layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeSpline {
+    /// Name of the edge's tail node
+    pub from: String,
+    /// Name of the edge's head node
+    pub to: String,
+    /// Control points of the piecewise cubic Bézier curve, in points
+    pub points: Vec<(f64, f64)>,
+    /// Tip of the arrowhead at the tail, if one is drawn
+    pub start: Option<(f64, f64)>,
+    /// Tip of the arrowhead at the head, if one is drawn
+    pub end: Option<(f64, f64)>,
+}
+
+/// A node's name, center position, and (width, height), in points.
+pub type NodeGeometry = (String, (f64, f64), (f64, f64));
+
+/// All geometry computed by a layout, detached from the graph.
+///
+/// Coordinates are in points (1/72 inch) with the origin at the lower-left
+/// corner and y growing upward.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutResult {
+    /// Each node's name, center position, and (width, height)
+    pub nodes: Vec<NodeGeometry>,
+    /// The routed edges
+    pub edges: Vec<EdgeSpline>,
+    /// Bounding box of the drawing as (llx, lly, urx, ury)
+    pub bbox: (f64, f64, f64, f64),
 }
 
 impl Context {
     /// Creates a new GraphViz context.
     ///
+    /// Each context reads the plugin configuration installed with GraphViz and
+    /// loads the plugins it lists on first use. GraphViz cannot share this
+    /// registry between contexts, so create one context and reuse it for every
+    /// layout and render, or use `new_with_plugins(true, false)` to skip plugin
+    /// discovery when the built-in plugins are enough.
+    ///
     /// # Returns
     ///
-    /// A Result containing the new Context or an error
+    /// A Result containing the new Context or an error, including
+    /// `GraphvizError::VersionMismatch` if the linked GraphViz predates 3.0.0
     pub fn new() -> Result<Self, GraphvizError> {
-        let inner = unsafe { sys::gvContext() };
+        let inner = serialized(|| {
+            PLUGIN_DIR_READ.store(true, AtomicOrdering::SeqCst);
+            unsafe { sys::gvContext() }
+        });
         
-        if inner.is_null() {
-            return Err(GraphvizError::ContextCreationFailed);
-        }
-        
-        Ok(Context { inner })
+        Context::from_inner(inner)
     }
     
     /// Creates a new GraphViz context with custom plugins.
     ///
+    /// With `builtins` set and `demand_loading` unset, only the plugins linked
+    /// into the GraphViz library are registered and the plugin configuration is
+    /// never read. This avoids the discovery cost of `new`, which dominates
+    /// startup for short-lived programs that render a single graph, but formats
+    /// and engines provided by separately installed plugins are unavailable.
+    ///
     /// # Arguments
     ///
     /// * `builtins` - Whether to include built-in plugins
@@ -102,7 +195,8 @@ impl Context {
     ///
     /// # Returns
     ///
-    /// A Result containing the new Context or an error
+    /// A Result containing the new Context or an error, including
+    /// `GraphvizError::VersionMismatch` if the linked GraphViz predates 3.0.0
     pub fn new_with_plugins(builtins: bool, demand_loading: bool) -> Result<Self, GraphvizError> {
         let builtins_ptr = if builtins {
             &raw const sys::lt_preloaded_symbols as *const _
@@ -110,27 +204,480 @@ impl Context {
             ptr::null()
         };
         
-        let inner = unsafe { 
-            sys::gvContextPlugins(builtins_ptr, if demand_loading { 1 } else { 0 }) 
-        };
+        let inner = serialized(|| {
+            if demand_loading {
+                PLUGIN_DIR_READ.store(true, AtomicOrdering::SeqCst);
+            }
+            
+            unsafe { sys::gvContextPlugins(builtins_ptr, if demand_loading { 1 } else { 0 }) }
+        });
+        
+        Context::from_inner(inner)
+    }
+    
+    /// Creates a new GraphViz context that loads plugins from a custom location.
+    ///
+    /// GraphViz looks for its plugins and their `config6` file in its install
+    /// prefix, or in the directory named by the `GVBINDIR` environment
+    /// variable. This sets `GVBINDIR` to `plugin_dir` while the context is
+    /// created and restores the previous value afterwards, which helps when
+    /// GraphViz is bundled with an application or installed in a nonstandard
+    /// prefix (common on Windows).
+    ///
+    /// GraphViz reads the plugin directory only once per process, when the
+    /// first context that loads plugins on demand is created, and keeps using
+    /// it for every later context, so call this before creating any other
+    /// context. GraphViz offers no way to pass the directory other than the
+    /// environment.
+    ///
+    /// # Safety
+    ///
+    /// This modifies the process environment, which is only sound while no
+    /// other thread reads or writes it. The lock around GraphViz calls does
+    /// not cover other readers, so call this at startup before spawning
+    /// threads, including the layout thread of `apply_layout_with_timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugin_dir` - The directory containing the plugin libraries and `config6`
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the new Context, `GraphvizError::IoError` if
+    /// `plugin_dir/config6` is missing, or `GraphvizError::InitializationFailed`
+    /// if GraphViz has already read its plugin directory
+    pub unsafe fn new_with_config(plugin_dir: &Path) -> Result<Self, GraphvizError> {
+        if !plugin_dir.join("config6").is_file() {
+            return Err(GraphvizError::IoError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "GraphViz plugin config file not found",
+            )));
+        }
+        
+        let inner = serialized(|| {
+            if PLUGIN_DIR_READ.swap(true, AtomicOrdering::SeqCst) {
+                return Err(GraphvizError::InitializationFailed);
+            }
+            
+            let previous = env::var_os("GVBINDIR");
+            env::set_var("GVBINDIR", plugin_dir);
+            let inner = unsafe { sys::gvContext() };
+            
+            match previous {
+                Some(previous) => env::set_var("GVBINDIR", previous),
+                None => env::remove_var("GVBINDIR"),
+            }
+            
+            Ok(inner)
+        })?;
         
+        Context::from_inner(inner)
+    }
+    
+    /// Wraps a newly created GraphViz context and checks its version.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The context returned by GraphViz, possibly null
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the Context or an error
+    fn from_inner(inner: *mut sys::GVC_t) -> Result<Self, GraphvizError> {
         if inner.is_null() {
             return Err(GraphvizError::ContextCreationFailed);
         }
         
-        Ok(Context { inner })
+        let context = Context {
+            inner,
+            handle: Arc::new(ContextHandle { inner }),
+            seed: Cell::new(None),
+        };
+        context.check_version()?;
+        
+        Ok(context)
+    }
+    
+    /// Seeds every layout made through this context.
+    ///
+    /// GraphViz has no process-wide random state to seed: the energy-based
+    /// engines (neato, fdp and sfdp) seed their generator from each graph's
+    /// `start` attribute. `apply_layout` therefore sets `start` to
+    /// `random<seed>` on graphs that do not set it themselves, so a whole run
+    /// of layouts is reproducible. The value is only in place while the layout
+    /// runs and is cleared again afterwards, so a later seed applies to the
+    /// same graph too. A graph's own `start`, for example from
+    /// `LayoutSettings::with_seed`, takes precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The random seed
+    pub fn set_random_seed(&self, seed: u32) {
+        self.seed.set(Some(seed));
+    }
+    
+    /// Lays out a graph and serializes the positioned graph in memory.
+    ///
+    /// With `Format::Xdot` (or `Format::Dot`) the output carries every computed
+    /// position, so an expensive layout can be done once and shipped to other
+    /// processes that only render. The layout stays attached to the graph and
+    /// can be released with `free_layout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to layout
+    /// * `engine` - The layout engine to use
+    /// * `format` - The serialization format
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the serialized graph or an error
+    pub fn layout_and_serialize(
+        &self,
+        graph: &mut Graph,
+        engine: Engine,
+        format: Format,
+    ) -> Result<Vec<u8>, GraphvizError> {
+        apply_layout(self, graph, engine)?;
+        render::render_to_bytes(self, graph, format)
+    }
+    
+    /// Lays out a graph and returns the computed geometry without rendering an image.
+    ///
+    /// GraphViz only records positions in the graph's attributes when it
+    /// writes DOT output, so the graph is serialized to DOT in memory and the
+    /// result discarded before the `pos`, `width`, `height` and `bb`
+    /// attributes are read. Those attributes stay set on the graph, and so does
+    /// the layout, which can be released with `free_layout`.
+    ///
+    /// Edges split into several splines (e.g. by `concentrate`) report only
+    /// their first spline.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to layout
+    /// * `engine` - The layout engine to use
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the layout geometry, or `GraphvizError::LayoutFailed`
+    /// if GraphViz produced positions that cannot be parsed
+    pub fn compute_layout(&self, graph: &mut Graph, engine: Engine) -> Result<LayoutResult, GraphvizError> {
+        self.layout_and_serialize(graph, engine, Format::Dot)?;
+        
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        
+        for node in graph.nodes() {
+            nodes.push(node_geometry(&node)?);
+            
+            for edge in graph.out_edges(&node) {
+                let pos = edge.get_attribute("pos")?.unwrap_or_default();
+                let mut spline = parse_spline(&pos)?;
+                spline.from = edge.tail().name()?;
+                spline.to = edge.head().name()?;
+                edges.push(spline);
+            }
+        }
+        
+        let bb = graph.get_attribute("bb")?.unwrap_or_default();
+        let bbox = match bb.split(',').map(parse_number).collect::<Result<Vec<_>, _>>()?[..] {
+            [llx, lly, urx, ury] => (llx, lly, urx, ury),
+            _ => return Err(GraphvizError::LayoutFailed),
+        };
+        
+        Ok(LayoutResult { nodes, edges, bbox })
+    }
+    
+    /// Gets the version of the GraphViz library linked at runtime.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the version string, e.g. "9.0.0", or an error
+    pub fn version(&self) -> Result<String, GraphvizError> {
+        c_str_to_string(unsafe { sys::gvcVersion(self.inner) }, "GraphViz version is null")
+    }
+    
+    /// Verifies that the linked GraphViz is new enough for the bindings' ABI.
+    ///
+    /// Called by every constructor so that a mismatched library is reported
+    /// at startup instead of corrupting memory during rendering.
+    fn check_version(&self) -> Result<(), GraphvizError> {
+        let detected = self.version()?;
+        
+        match parse_version(&detected) {
+            Some(version) if version >= MIN_GRAPHVIZ_VERSION => Ok(()),
+            _ => Err(GraphvizError::VersionMismatch {
+                detected,
+                expected: format!(
+                    ">= {}.{}.{}",
+                    MIN_GRAPHVIZ_VERSION.0, MIN_GRAPHVIZ_VERSION.1, MIN_GRAPHVIZ_VERSION.2
+                ),
+            }),
+        }
+    }
+    
+    /// Collects version and plugin details of the linked GraphViz library.
+    ///
+    /// This is intended for bug reports and for checking at runtime whether
+    /// a layout engine or output format is installed.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the build information or an error
+    pub fn build_info(&self) -> Result<BuildInfo, GraphvizError> {
+        Ok(BuildInfo {
+            version: self.version()?,
+            build_date: c_str_to_string(unsafe { sys::gvcBuildDate(self.inner) }, "GraphViz build date is null")?,
+            layout_engines: self.plugin_list("layout")?,
+            formats: self.plugin_list("device")?,
+        })
+    }
+    
+    /// Lists the names of the loaded plugins of one kind, such as "layout" or "device".
+    pub(crate) fn plugin_list(&self, kind: &str) -> Result<Vec<String>, GraphvizError> {
+        extern "C" {
+            fn free(ptr: *mut c_void);
+        }
+        
+        let kind = CString::new(kind)?;
+        let mut count: c_int = 0;
+        let list = unsafe { sys::gvPluginList(self.inner, kind.as_ptr(), &mut count) };
+        
+        if list.is_null() {
+            return Ok(Vec::new());
+        }
+        
+        let mut names = Vec::new();
+        
+        for i in 0..count.max(0) as usize {
+            let entry = unsafe { *list.add(i) };
+            
+            if !entry.is_null() {
+                names.push(unsafe { CStr::from_ptr(entry) }.to_string_lossy().into_owned());
+                unsafe { free(entry as *mut c_void) };
+            }
+        }
+        
+        unsafe { free(list as *mut c_void) };
+        
+        Ok(names)
+    }
+}
+
+/// Sets the process-wide directories searched for font files.
+///
+/// Prefer `Graph::set_fontpath`, which sets the `fontpath` attribute on a
+/// single graph. This sets the `DOTFONTPATH` and `GDFONTPATH` environment
+/// variables read by the renderers instead, for graphs without `fontpath`.
+/// Cairo-based renderers find fonts through fontconfig instead; see
+/// `set_font_config`.
+///
+/// # Safety
+///
+/// This modifies the process environment, which is only sound while no other
+/// thread reads or writes it. Call it at startup before spawning threads,
+/// including the layout thread of `apply_layout_with_timeout`; GraphViz and
+/// fontconfig read the environment during layouts and renders.
+///
+/// # Arguments
+///
+/// * `dirs` - The font directories, in search order
+///
+/// # Returns
+///
+/// A Result indicating success or failure
+pub unsafe fn set_font_path(dirs: &[&Path]) -> Result<(), GraphvizError> {
+    let joined = env::join_paths(dirs).map_err(|err| {
+        GraphvizError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidInput, err))
+    })?;
+    
+    env::set_var("DOTFONTPATH", &joined);
+    env::set_var("GDFONTPATH", &joined);
+    
+    Ok(())
+}
+
+/// Selects the fontconfig configuration file used by Cairo-based renderers.
+///
+/// This sets the `FONTCONFIG_FILE` environment variable for the whole process.
+/// fontconfig reads it once on first use.
+///
+/// # Safety
+///
+/// This modifies the process environment and has the same requirements as
+/// `set_font_path`: no other thread may be running.
+///
+/// # Arguments
+///
+/// * `config_file` - Path to a `fonts.conf` file
+///
+/// # Returns
+///
+/// A Result indicating success or failure
+pub unsafe fn set_font_config(config_file: &Path) -> Result<(), GraphvizError> {
+    if !config_file.is_file() {
+        return Err(GraphvizError::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "fontconfig file not found",
+        )));
+    }
+    
+    env::set_var("FONTCONFIG_FILE", config_file);
+    
+    Ok(())
+}
+
+/// Gets the version of the GraphViz library linked at runtime.
+///
+/// A temporary context is created for the query; use `Context::version` when
+/// a context is already at hand.
+///
+/// # Returns
+///
+/// A Result containing the version string or an error
+pub fn graphviz_version() -> Result<String, GraphvizError> {
+    Context::new()?.version()
+}
+
+/// Parses the leading major.minor.patch numbers of a GraphViz version string.
+///
+/// Suffixes such as `~dev.20240101` are ignored and a missing patch number
+/// counts as zero.
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.split('.').map(|part| {
+        let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse::<u32>().ok()
+    });
+    
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next().flatten().unwrap_or(0);
+    
+    Some((major, minor, patch))
+}
+
+/// Records an active layout in the graph's `pos`, `width`, `height` and `bb` attributes.
+///
+/// GraphViz only writes these attributes when emitting DOT output, so the
+/// graph is rendered to DOT in memory and the output discarded.
+pub(crate) fn attach_layout_attributes(context: &ContextHandle, graph: &Graph) -> Result<(), GraphvizError> {
+    let format = CString::new("dot")?;
+    let mut buffer: *mut c_char = ptr::null_mut();
+    let mut length: usize = 0;
+    
+    let result = serialized(|| unsafe {
+        sys::gvRenderData(context.inner, graph.inner, format.as_ptr(), &mut buffer, &mut length)
+    });
+    
+    if !buffer.is_null() {
+        unsafe { sys::gvFreeRenderData(buffer) };
+    }
+    
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(GraphvizError::RenderFailed)
+    }
+}
+
+/// Parses a number from a layout attribute.
+fn parse_number(value: &str) -> Result<f64, GraphvizError> {
+    value.trim().parse().map_err(|_| GraphvizError::LayoutFailed)
+}
+
+/// Parses an `x,y` point, ignoring the `!` suffix of pinned nodes.
+fn parse_point(value: &str) -> Result<(f64, f64), GraphvizError> {
+    let value = value.trim().trim_end_matches('!');
+    let (x, y) = value.split_once(',').ok_or(GraphvizError::LayoutFailed)?;
+    
+    Ok((parse_number(x)?, parse_number(y)?))
+}
+
+/// Reads a node's name, position and size from its layout attributes.
+///
+/// `width` and `height` are given in inches and converted to points.
+pub(crate) fn node_geometry(node: &Node) -> Result<NodeGeometry, GraphvizError> {
+    let pos = node.get_attribute("pos")?.unwrap_or_default();
+    let width = node.get_attribute("width")?.unwrap_or_default();
+    let height = node.get_attribute("height")?.unwrap_or_default();
+    let size = (parse_number(&width)? * 72.0, parse_number(&height)? * 72.0);
+    
+    Ok((node.name()?, parse_point(&pos)?, size))
+}
+
+/// Parses the first spline of an edge `pos` attribute, e.g. `e,10,20 1,2 3,4 5,6 7,8`.
+///
+/// The endpoint names are left empty for the caller to fill in.
+fn parse_spline(value: &str) -> Result<EdgeSpline, GraphvizError> {
+    let mut spline = EdgeSpline {
+        from: String::new(),
+        to: String::new(),
+        points: Vec::new(),
+        start: None,
+        end: None,
+    };
+    
+    for token in value.split(';').next().unwrap_or_default().split_whitespace() {
+        if let Some(point) = token.strip_prefix("s,") {
+            spline.start = Some(parse_point(point)?);
+        } else if let Some(point) = token.strip_prefix("e,") {
+            spline.end = Some(parse_point(point)?);
+        } else {
+            spline.points.push(parse_point(token)?);
+        }
     }
+    
+    Ok(spline)
 }
 
-// RAII implementation for Context
-impl Drop for Context {
+/// Copies a C string owned by GraphViz into a String.
+fn c_str_to_string(ptr: *const c_char, context: &'static str) -> Result<String, GraphvizError> {
+    if ptr.is_null() {
+        return Err(GraphvizError::NullPointer(context));
+    }
+    
+    Ok(unsafe { CStr::from_ptr(ptr) }.to_str()?.to_owned())
+}
+
+// RAII implementation for the shared context handle
+impl Drop for ContextHandle {
     fn drop(&mut self) {
         if !self.inner.is_null() {
-            unsafe { sys::gvFreeContext(self.inner) };
+            serialized(|| unsafe { sys::gvFreeContext(self.inner) });
         }
     }
 }
 
+/// Serializes calls into GraphViz's layout and rendering library (libgvc) and its DOT parser.
+///
+/// The layout engines, renderers and parser keep process-global state, so at
+/// most one such call may run at a time. This matters once `apply_layout_with_timeout`
+/// abandons a layout: the helper thread keeps the lock until its layout
+/// returns, and every later layout, render, or context call waits for it.
+static GVC_LOCK: Mutex<()> = Mutex::new(());
+
+/// Whether a context that reads the plugin directory has been created.
+///
+/// GraphViz caches the plugin directory (`GVBINDIR`) on first use for the
+/// rest of the process, so `Context::new_with_config` can only take effect
+/// before this is set.
+static PLUGIN_DIR_READ: AtomicBool = AtomicBool::new(false);
+
+/// Runs a libgvc or parser call while holding the crate-wide GraphViz lock.
+///
+/// # Arguments
+///
+/// * `call` - The GraphViz call to run
+///
+/// # Returns
+///
+/// The result of the call
+pub(crate) fn serialized<T>(call: impl FnOnce() -> T) -> T {
+    let _guard = GVC_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    call()
+}
+
 /// Applies a layout to a graph using the specified engine.
 ///
 /// # Arguments
@@ -148,20 +695,150 @@ pub fn apply_layout(
     engine: Engine
 ) -> Result<(), GraphvizError> {
     let engine_cstr = engine.as_cstr()?;
+    let seeded = match context.seed.get() {
+        Some(seed) if graph.get_attribute("start")?.is_none_or(|start| start.is_empty()) => {
+            graph.set_attribute("start", &format!("random{}", seed))?;
+            true
+        }
+        _ => false,
+    };
     
-    let result = unsafe { 
+    let result = serialized(|| unsafe { 
         sys::gvLayout(context.inner, graph.inner, engine_cstr.as_ptr()) 
-    };
+    });
+    
+    // The seed belongs to the context, not the graph; don't let it pose as the graph's own `start`
+    if seeded {
+        graph.set_attribute("start", "")?;
+    }
     
     if result == 0 {
+        graph.layout_context = Some(Arc::clone(&context.handle));
         Ok(())
     } else {
         Err(GraphvizError::LayoutFailed)
     }
 }
 
+/// Applies a layout on a helper thread, abandoning it after a timeout.
+///
+/// GraphViz layout calls cannot be interrupted, so the context and graph are
+/// moved to the helper thread and handed back only if the layout finishes in
+/// time. On timeout the helper thread keeps running until the layout
+/// completes and then frees both. For a cooperative limit that bounds the work
+/// itself, see `LayoutSettings::with_nslimit`, `with_mclimit` and
+/// `with_maxiter`.
+///
+/// GraphViz is not thread-safe. Every vizoxide call into its layout and
+/// rendering library takes a crate-wide lock, which the helper thread holds
+/// for the whole layout, so after a timeout any further layout, render, or
+/// context creation blocks until the abandoned layout has finished. Calls that
+/// only build or edit graphs are not serialized; after a timeout, call
+/// `wait_for_abandoned_layouts` before running them on any thread.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to layout
+/// * `engine` - The layout engine to use
+/// * `timeout` - How long to wait for the layout to finish
+///
+/// # Returns
+///
+/// A Result containing the context and the laid out graph, or
+/// `GraphvizError::LayoutTimeout` if the layout did not finish in time
+pub fn apply_layout_with_timeout(
+    context: Context,
+    graph: Graph,
+    engine: Engine,
+    timeout: Duration,
+) -> Result<(Context, Graph), GraphvizError> {
+    let (sender, receiver) = mpsc::channel();
+    let job = LayoutJob { context, graph };
+    let running = LayoutThread::start();
+    
+    thread::Builder::new()
+        .name("vizoxide-layout".to_owned())
+        .spawn(move || {
+            // Declared first so it is dropped last, after the job has been freed
+            let _running = running;
+            let mut job = job;
+            let result = apply_layout(&job.context, &mut job.graph, engine);
+            // The receiver is gone after a timeout; the job is then dropped here
+            let _ = sender.send((result, job));
+        })?;
+    
+    match receiver.recv_timeout(timeout) {
+        Ok((result, job)) => result.map(|()| (job.context, job.graph)),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(GraphvizError::LayoutTimeout),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(GraphvizError::LayoutFailed),
+    }
+}
+
+/// Blocks until every layout abandoned by `apply_layout_with_timeout` has finished.
+///
+/// Returns once each helper thread started by `apply_layout_with_timeout`
+/// has completed its layout and freed the graph and context it was given,
+/// after which graphs can again be built and edited on any thread.
+pub fn wait_for_abandoned_layouts() {
+    let mut running = layout_threads();
+    
+    while *running > 0 {
+        running = LAYOUT_THREADS_DONE.wait(running).unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+}
+
+/// Number of `apply_layout_with_timeout` helper threads that have not yet finished.
+static LAYOUT_THREADS: Mutex<usize> = Mutex::new(0);
+
+/// Signalled when `LAYOUT_THREADS` drops to zero.
+static LAYOUT_THREADS_DONE: Condvar = Condvar::new();
+
+/// Locks the count of running layout helper threads.
+fn layout_threads() -> MutexGuard<'static, usize> {
+    LAYOUT_THREADS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Counts a layout helper thread as running until dropped.
+struct LayoutThread;
+
+// LayoutThread implementation
+impl LayoutThread {
+    /// Registers a helper thread that is about to be started.
+    fn start() -> Self {
+        *layout_threads() += 1;
+        LayoutThread
+    }
+}
+
+impl Drop for LayoutThread {
+    fn drop(&mut self) {
+        let mut running = layout_threads();
+        *running -= 1;
+        
+        if *running == 0 {
+            LAYOUT_THREADS_DONE.notify_all();
+        }
+    }
+}
+
+/// A context and graph moved to a helper thread for layout.
+struct LayoutJob {
+    /// The context used for the layout
+    context: Context,
+    /// The graph being laid out
+    graph: Graph,
+}
+
+// The job is only ever accessed by one thread at a time: the helper thread
+// owns it until it is sent back or dropped.
+unsafe impl Send for LayoutJob {}
+
 /// Frees the layout resources associated with a graph.
 ///
+/// Dropping a graph frees its layout automatically; call this to release the
+/// memory earlier or before laying the graph out again.
+///
 /// # Arguments
 ///
 /// * `context` - The GraphViz context
@@ -174,23 +851,92 @@ pub fn free_layout(
     context: &Context,
     graph: &mut Graph,
 ) -> Result<(), GraphvizError> {
-    let result = unsafe { 
+    let result = serialized(|| unsafe { 
         sys::gvFreeLayout(context.inner, graph.inner) 
-    };
+    });
     
     if result == 0 {
+        graph.layout_context = None;
         Ok(())
     } else {
         Err(GraphvizError::FreeLayoutFailed)
     }
 }
 
+/// Rank separation for the `ranksep` attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RankSep {
+    /// A fixed minimum separation between ranks, in inches.
+    Uniform(f64),
+    /// Space the centers of all ranks equally, regardless of their contents (dot).
+    Equally,
+    /// Separation between each successive pair of ranks, in inches (twopi).
+    PerRank(Vec<f64>),
+}
+
+impl RankSep {
+    /// Converts the rank separation to its attribute value.
+    ///
+    /// # Returns
+    ///
+    /// The attribute value as a string
+    pub fn to_attribute(&self) -> String {
+        match self {
+            RankSep::Uniform(separation) => separation.to_string(),
+            RankSep::Equally => "equally".to_owned(),
+            RankSep::PerRank(separations) => separations
+                .iter()
+                .map(|separation| separation.to_string())
+                .collect::<Vec<_>>()
+                .join(":"),
+        }
+    }
+}
+
+impl From<f64> for RankSep {
+    fn from(separation: f64) -> Self {
+        RankSep::Uniform(separation)
+    }
+}
+
+/// A symbolic value for the `ratio` attribute, controlling how the drawing
+/// is fitted to the `size` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RatioMode {
+    /// Scale each axis independently so the drawing exactly fills `size`.
+    Fill,
+    /// Compress the initial layout to fit within `size` (dot only).
+    Compress,
+    /// Scale the drawing up uniformly until it reaches `size` in one dimension.
+    Expand,
+    /// Let `page` decide: rotate or compress the drawing to fit one page.
+    Auto,
+}
+
+impl RatioMode {
+    /// Gets the GraphViz value for the `ratio` attribute.
+    ///
+    /// # Returns
+    ///
+    /// The attribute value as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RatioMode::Fill => "fill",
+            RatioMode::Compress => "compress",
+            RatioMode::Expand => "expand",
+            RatioMode::Auto => "auto",
+        }
+    }
+}
+
 /// Layout settings for configuring layout algorithms.
 pub struct LayoutSettings {
     /// Size of the output (in inches).
     pub size: Option<(f64, f64)>,
     /// Ratio of height/width.
     pub ratio: Option<f64>,
+    /// Symbolic ratio, applied instead of `ratio` when set.
+    pub ratio_mode: Option<RatioMode>,
     /// Direction of layout.
     pub rankdir: Option<String>,
     /// Overlap removal strategy.
@@ -198,7 +944,7 @@ pub struct LayoutSettings {
     /// Separation between nodes.
     pub nodesep: Option<f64>,
     /// Separation between ranks.
-    pub ranksep: Option<f64>,
+    pub ranksep: Option<RankSep>,
     /// Spline configuration.
     pub splines: Option<String>,
     /// Margin around the layout.
@@ -215,6 +961,24 @@ pub struct LayoutSettings {
     pub orientation: Option<f64>,
     /// Edge concentration.
     pub concentrate: Option<bool>,
+    /// Network simplex iteration factor for ranking (dot).
+    pub nslimit: Option<f64>,
+    /// Network simplex iteration factor for x-coordinates (dot).
+    pub nslimit1: Option<f64>,
+    /// Crossing minimization iteration factor (dot).
+    pub mclimit: Option<f64>,
+    /// Maximum number of layout iterations (neato, fdp).
+    pub maxiter: Option<i32>,
+    /// Random seed for the initial node placement (neato, fdp, sfdp).
+    pub seed: Option<i64>,
+    /// Resolution used while computing the layout.
+    pub dpi: Option<f64>,
+    /// Page size for paginated output (in inches).
+    pub page: Option<(f64, f64)>,
+    /// Order in which pages are emitted.
+    pub pagedir: Option<String>,
+    /// Edge ordering constraint (dot).
+    pub ordering: Option<String>,
 }
 
 impl Default for LayoutSettings {
@@ -222,6 +986,7 @@ impl Default for LayoutSettings {
         LayoutSettings {
             size: None,
             ratio: None,
+            ratio_mode: None,
             rankdir: None,
             overlap: None,
             nodesep: None,
@@ -234,6 +999,15 @@ impl Default for LayoutSettings {
             minlen: None,
             orientation: None,
             concentrate: None,
+            nslimit: None,
+            nslimit1: None,
+            mclimit: None,
+            maxiter: None,
+            seed: None,
+            dpi: None,
+            page: None,
+            pagedir: None,
+            ordering: None,
         }
     }
 }
@@ -262,7 +1036,9 @@ impl LayoutSettings {
             graph.set_attribute("size", &format!("{},{}!", width, height))?;
         }
         
-        if let Some(ratio) = self.ratio {
+        if let Some(mode) = self.ratio_mode {
+            graph.set_attribute("ratio", mode.as_str())?;
+        } else if let Some(ratio) = self.ratio {
             graph.set_attribute("ratio", &ratio.to_string())?;
         }
         
@@ -278,8 +1054,8 @@ impl LayoutSettings {
             graph.set_attribute("nodesep", &nodesep.to_string())?;
         }
         
-        if let Some(ranksep) = self.ranksep {
-            graph.set_attribute("ranksep", &ranksep.to_string())?;
+        if let Some(ref ranksep) = self.ranksep {
+            graph.set_attribute("ranksep", &ranksep.to_attribute())?;
         }
         
         if let Some(ref splines) = self.splines {
@@ -314,6 +1090,42 @@ impl LayoutSettings {
             graph.set_attribute("concentrate", if concentrate { "true" } else { "false" })?;
         }
         
+        if let Some(nslimit) = self.nslimit {
+            graph.set_attribute("nslimit", &nslimit.to_string())?;
+        }
+        
+        if let Some(nslimit1) = self.nslimit1 {
+            graph.set_attribute("nslimit1", &nslimit1.to_string())?;
+        }
+        
+        if let Some(mclimit) = self.mclimit {
+            graph.set_attribute("mclimit", &mclimit.to_string())?;
+        }
+        
+        if let Some(maxiter) = self.maxiter {
+            graph.set_attribute("maxiter", &maxiter.to_string())?;
+        }
+        
+        if let Some(seed) = self.seed {
+            graph.set_attribute("start", &format!("random{}", seed))?;
+        }
+        
+        if let Some(dpi) = self.dpi {
+            graph.set_attribute("dpi", &dpi.to_string())?;
+        }
+        
+        if let Some((width, height)) = self.page {
+            graph.set_attribute("page", &format!("{},{}", width, height))?;
+        }
+        
+        if let Some(ref pagedir) = self.pagedir {
+            graph.set_attribute("pagedir", pagedir)?;
+        }
+        
+        if let Some(ref ordering) = self.ordering {
+            graph.set_attribute("ordering", ordering)?;
+        }
+        
         Ok(())
     }
     
@@ -343,6 +1155,26 @@ impl LayoutSettings {
     /// Self for method chaining
     pub fn with_ratio(mut self, ratio: f64) -> Self {
         self.ratio = Some(ratio);
+        self.ratio_mode = None;
+        self
+    }
+    
+    /// Sets a symbolic ratio controlling how the drawing fits `size`.
+    ///
+    /// Combined with `with_size`, `RatioMode::Fill` makes the drawing exactly
+    /// fill the given area, which is the usual way to fit a graph to a page.
+    /// Replaces any numeric ratio set with `with_ratio`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The ratio mode
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_ratio_mode(mut self, mode: RatioMode) -> Self {
+        self.ratio_mode = Some(mode);
+        self.ratio = None;
         self
     }
     
@@ -390,6 +1222,8 @@ impl LayoutSettings {
     
     /// Sets the rank separation.
     ///
+    /// Accepts either a plain separation in inches or a `RankSep` value.
+    ///
     /// # Arguments
     ///
     /// * `ranksep` - The separation value
@@ -397,8 +1231,8 @@ impl LayoutSettings {
     /// # Returns
     ///
     /// Self for method chaining
-    pub fn with_ranksep(mut self, ranksep: f64) -> Self {
-        self.ranksep = Some(ranksep);
+    pub fn with_ranksep<R: Into<RankSep>>(mut self, ranksep: R) -> Self {
+        self.ranksep = Some(ranksep.into());
         self
     }
     
@@ -416,6 +1250,21 @@ impl LayoutSettings {
         self
     }
     
+    /// Sets the spline configuration from a typed value.
+    ///
+    /// See `Graph::set_splines` for the restrictions of `Splines::Ortho`.
+    ///
+    /// # Arguments
+    ///
+    /// * `splines` - The edge routing style
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_splines_typed(self, splines: Splines) -> Self {
+        self.with_splines(splines.as_str())
+    }
+    
     /// Sets the margin around the layout.
     ///
     /// # Arguments
@@ -503,6 +1352,10 @@ impl LayoutSettings {
     
     /// Sets whether to concentrate edges.
     ///
+    /// With the dot engine, parallel edges between the same pair of nodes are
+    /// then drawn as a single edge. For a merge that does not depend on the
+    /// layout engine, see `Graph::collapse_parallel_edges`.
+    ///
     /// # Arguments
     ///
     /// * `concentrate` - Whether to concentrate edges
@@ -514,6 +1367,165 @@ impl LayoutSettings {
         self.concentrate = Some(concentrate);
         self
     }
+    
+    /// Limits the network simplex iterations used for ranking and
+    /// x-coordinate placement.
+    ///
+    /// Applies to dot. The iteration bound is the factor times the number of
+    /// nodes; smaller values trade layout quality for speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `nslimit` - The iteration factor for both network simplex passes
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_nslimit(mut self, nslimit: f64) -> Self {
+        self.nslimit = Some(nslimit);
+        self.nslimit1 = Some(nslimit);
+        self
+    }
+    
+    /// Limits the network simplex iterations used for ranking only.
+    ///
+    /// Applies to dot, overriding the ranking factor set by `with_nslimit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `nslimit1` - The iteration factor for ranking
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_nslimit1(mut self, nslimit1: f64) -> Self {
+        self.nslimit1 = Some(nslimit1);
+        self
+    }
+    
+    /// Scales the number of crossing minimization iterations.
+    ///
+    /// Applies to dot. Values below 1 shorten crossing minimization.
+    ///
+    /// # Arguments
+    ///
+    /// * `mclimit` - The iteration scale factor
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_mclimit(mut self, mclimit: f64) -> Self {
+        self.mclimit = Some(mclimit);
+        self
+    }
+    
+    /// Sets the maximum number of layout iterations.
+    ///
+    /// Applies to neato and fdp.
+    ///
+    /// # Arguments
+    ///
+    /// * `maxiter` - The maximum number of iterations
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_maxiter(mut self, maxiter: i32) -> Self {
+        self.maxiter = Some(maxiter);
+        self
+    }
+    
+    /// Seeds the random initial placement so layouts are reproducible.
+    ///
+    /// Sets the `start` attribute to `random<seed>`. Only the energy-based
+    /// engines (neato, fdp and sfdp) honor it; the other engines are
+    /// deterministic already.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The random seed
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_seed(mut self, seed: i64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    
+    /// Sets the resolution used while computing the layout.
+    ///
+    /// GraphViz reads `dpi` once, when the layout is computed, and keeps using
+    /// that value to convert point coordinates to pixels when rendering raster
+    /// formats. Set it here so the layout and the raster agree; a `dpi` set by
+    /// `RenderOptions` after layout does not change the output resolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `dpi` - The resolution in dots per inch
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_dpi(mut self, dpi: f64) -> Self {
+        self.dpi = Some(dpi);
+        self
+    }
+    
+    /// Splits the output into pages of the given size.
+    ///
+    /// Only the PostScript and PDF renderers emit multiple pages; other
+    /// formats draw just the first page. `size` still scales the whole drawing
+    /// first, and the scaled drawing is then tiled onto pages, so leave `size`
+    /// unset to print at natural size across as many pages as needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The page width in inches
+    /// * `height` - The page height in inches
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_page(mut self, width: f64, height: f64) -> Self {
+        self.page = Some((width, height));
+        self
+    }
+    
+    /// Sets the order in which pages are emitted.
+    ///
+    /// The value is a pair of directions from `B`, `L`, `R` and `T`, major
+    /// direction first; the default `BL` emits rows bottom to top, each row
+    /// left to right.
+    ///
+    /// # Arguments
+    ///
+    /// * `pagedir` - The page order, such as "TL"
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_pagedir(mut self, pagedir: &str) -> Self {
+        self.pagedir = Some(pagedir.to_owned());
+        self
+    }
+    
+    /// Sets the edge ordering constraint for the whole graph.
+    ///
+    /// With "out", dot keeps each node's children in the order their edges
+    /// were added; with "in", it does the same for parents.
+    ///
+    /// # Arguments
+    ///
+    /// * `ordering` - The ordering, "out" or "in"
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_ordering(mut self, ordering: &str) -> Self {
+        self.ordering = Some(ordering.to_owned());
+        self
+    }
 }
 
 /// Creates a predefined set of layout settings for a hierarchical layout.
@@ -542,6 +1554,40 @@ pub fn left_to_right_layout() -> LayoutSettings {
         .with_ranksep(0.5)
 }
 
+/// Creates a predefined set of layout settings for a compact tree layout.
+///
+/// Siblings keep their insertion order and edges are drawn as straight
+/// lines with tight spacing, which suits file-system-style trees.
+///
+/// # Returns
+///
+/// A LayoutSettings instance configured for compact tree layout
+pub fn compact_tree_layout() -> LayoutSettings {
+    LayoutSettings::new()
+        .with_rankdir("TB")
+        .with_splines("line")
+        .with_ordering("out")
+        .with_nodesep(0.15)
+        .with_ranksep(0.3)
+}
+
+/// Creates a predefined set of layout settings for a wide tree layout.
+///
+/// Like `compact_tree_layout`, but with generous spacing between siblings
+/// and ranks for trees with long labels.
+///
+/// # Returns
+///
+/// A LayoutSettings instance configured for wide tree layout
+pub fn wide_tree_layout() -> LayoutSettings {
+    LayoutSettings::new()
+        .with_rankdir("TB")
+        .with_splines("line")
+        .with_ordering("out")
+        .with_nodesep(0.8)
+        .with_ranksep(0.6)
+}
+
 /// Creates a predefined set of layout settings for a radial layout.
 ///
 /// # Returns
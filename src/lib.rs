@@ -118,13 +118,22 @@
 //! ```
 
 // Re-export from modules
-pub use crate::graph::{Graph, Node, Edge, GraphBuilder, NodeBuilder, EdgeBuilder};
-pub use crate::layout::Context;
+pub use crate::graph::{Graph, Node, Edge, GraphBuilder, NodeBuilder, EdgeBuilder, PolygonBuilder, AlignmentGroup, NodeTemplate, EdgeTemplate, Subgraph, GraphRef, ValidationWarning, GraphStats, GraphKind};
+pub use crate::layout::{Context, graphviz_version};
 pub use crate::error::GraphvizError;
+pub use crate::data::GraphData;
+pub use crate::diff::GraphDiff;
+pub use crate::render::render_graph;
+
+// Re-export the bindings so raw pointers from `as_raw` match the caller's types
+pub use graphviz_sys as sys;
 
 // Public modules
 pub mod graph;
 pub mod layout;
 pub mod render;
 pub mod attr;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod data;
+pub mod dot;
+pub mod diff;
\ No newline at end of file
@@ -2,20 +2,23 @@
 //!
 //! This module provides functions for rendering GraphViz graphs to various formats.
 
-use std::ffi::CString;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::fs;
 use std::path::Path;
 use std::io::Write;
 use std::slice;
 use std::str;
+use std::sync::{Mutex, MutexGuard};
 
 use base64::Engine;
 use graphviz_sys as sys;
-use crate::error::GraphvizError;
-use crate::graph::Graph;
-use crate::layout::Context;
+use crate::error::{self, GraphvizError};
+use crate::graph::{Graph, Subgraph};
+use crate::layout::{self, Context};
 
 /// A GraphViz output format.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Format {
     /// Portable Network Graphics format.
     Png,
@@ -53,6 +56,12 @@ pub enum Format {
     Bmp,
     /// SVG with embedded XHTML format.
     Svgz,
+    /// WebP format (requires a plugin with WebP support).
+    Webp,
+    /// TIFF format (requires a plugin with TIFF support).
+    Tiff,
+    /// Windows icon format (requires a plugin with ICO support).
+    Ico,
 }
 
 impl Format {
@@ -81,11 +90,30 @@ impl Format {
             Format::Imap => "imap",
             Format::Bmp => "bmp",
             Format::Svgz => "svgz",
+            Format::Webp => "webp",
+            Format::Tiff => "tiff",
+            Format::Ico => "ico",
         };
         
         CString::new(name).map_err(|_| GraphvizError::InvalidFormat)
     }
     
+    /// Converts the format to a C string selecting a specific renderer.
+    ///
+    /// # Arguments
+    ///
+    /// * `renderer` - The renderer suffix, e.g. "cairo" for `png:cairo`
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the C string or an error
+    pub(crate) fn as_cstr_with_renderer(&self, renderer: &str) -> Result<CString, GraphvizError> {
+        let format = self.as_cstr()?;
+        let name = format!("{}:{}", format.to_str().map_err(|_| GraphvizError::InvalidFormat)?, renderer);
+        
+        CString::new(name).map_err(|_| GraphvizError::InvalidFormat)
+    }
+    
     /// Checks if the format is binary.
     ///
     /// # Returns
@@ -94,13 +122,27 @@ impl Format {
     pub fn is_binary(&self) -> bool {
         match self {
             Format::Png | Format::Gif | Format::Jpeg | Format::Pdf |
-            Format::Bmp | Format::Svgz => true,
+            Format::Bmp | Format::Svgz | Format::Webp | Format::Tiff |
+            Format::Ico => true,
             Format::Svg | Format::Dot | Format::Xdot | Format::Plain |
             Format::Canon | Format::Json | Format::Ps | Format::Eps |
             Format::Fig | Format::Vrml | Format::Cmapx | Format::Imap => false,
         }
     }
     
+    /// Checks if the format is a raster (bitmap) image.
+    ///
+    /// # Returns
+    ///
+    /// true if the format is a raster image, false for vector and text formats
+    pub fn is_raster(&self) -> bool {
+        matches!(
+            self,
+            Format::Png | Format::Gif | Format::Jpeg | Format::Bmp |
+            Format::Webp | Format::Tiff | Format::Ico
+        )
+    }
+    
     /// Returns an iterator over all available output formats.
     ///
     /// # Returns
@@ -126,6 +168,9 @@ impl Format {
             Format::Imap,
             Format::Bmp,
             Format::Svgz,
+            Format::Webp,
+            Format::Tiff,
+            Format::Ico,
         ].iter().copied()
     }
     
@@ -154,6 +199,9 @@ impl Format {
             Format::Imap => "application/x-httpd-imap",
             Format::Bmp => "image/bmp",
             Format::Svgz => "image/svg+xml",
+            Format::Webp => "image/webp",
+            Format::Tiff => "image/tiff",
+            Format::Ico => "image/vnd.microsoft.icon",
         }
     }
     
@@ -182,6 +230,9 @@ impl Format {
             Format::Imap => "map",
             Format::Bmp => "bmp",
             Format::Svgz => "svgz",
+            Format::Webp => "webp",
+            Format::Tiff => "tiff",
+            Format::Ico => "ico",
         }
     }
 }
@@ -204,24 +255,234 @@ pub fn render_to_file<P: AsRef<Path>>(
     format: Format,
     path: P,
 ) -> Result<(), GraphvizError> {
-    let format_cstr = format.as_cstr()?;
-    let path_str = path.as_ref().to_string_lossy();
-    let path_cstr = CString::new(path_str.as_bytes())?;
+    render_file(context, graph, &format.as_cstr()?, path.as_ref())
+}
+
+/// Renders a graph to a file with the specified format and renderer.
+///
+/// GraphViz often has several renderers for one format, selected with a
+/// `format:renderer` suffix such as `png:cairo` or `png:gd`. The choice
+/// affects anti-aliasing and font handling. Available renderers depend on the
+/// installed plugins.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to render
+/// * `format` - The output format
+/// * `renderer` - The renderer suffix, e.g. "cairo", "gd" or "core"
+/// * `path` - The output file path
+///
+/// # Returns
+///
+/// A Result indicating success or failure
+pub fn render_to_file_with_renderer<P: AsRef<Path>>(
+    context: &Context,
+    graph: &Graph,
+    format: Format,
+    renderer: &str,
+    path: P,
+) -> Result<(), GraphvizError> {
+    render_file(context, graph, &format.as_cstr_with_renderer(renderer)?, path.as_ref())
+}
+
+/// Renders a graph to a file using a GraphViz format string.
+fn render_file(
+    context: &Context,
+    graph: &Graph,
+    format_cstr: &CStr,
+    path: &Path,
+) -> Result<(), GraphvizError> {
+    let path_str = path.to_string_lossy();
+    let path_cstr = error::c_string(path_str.as_bytes(), "output path")?;
     
-    let result = unsafe {
+    let result = layout::serialized(|| unsafe {
         sys::gvRenderFilename(
             context.inner,
             graph.inner,
             format_cstr.as_ptr(),
             path_cstr.as_ptr(),
         )
-    };
+    });
     
     if result == 0 {
         Ok(())
     } else {
-        Err(GraphvizError::RenderFailed)
+        Err(render_error(context, format_cstr))
+    }
+}
+
+/// Lays out and renders a graph to a file in one call.
+///
+/// A throwaway context is created for the call and the layout is freed
+/// afterwards. Use `Context`, `layout::apply_layout` and `render_to_file`
+/// directly to reuse a context or render the same layout several times.
+///
+/// # Arguments
+///
+/// * `graph` - The graph to render
+/// * `engine` - The layout engine to use
+/// * `format` - The output format
+/// * `path` - The output file path
+///
+/// # Returns
+///
+/// A Result indicating success or failure
+pub fn render_graph<P: AsRef<Path>>(
+    graph: &mut Graph,
+    engine: layout::Engine,
+    format: Format,
+    path: P,
+) -> Result<(), GraphvizError> {
+    let context = Context::new()?;
+    layout::apply_layout(&context, graph, engine)?;
+    let rendered = render_to_file(&context, graph, format, path);
+    layout::free_layout(&context, graph)?;
+    rendered
+}
+
+/// Lays out and renders a graph as a PNG that fits within a pixel box.
+///
+/// The drawing is scaled up or down, preserving its aspect ratio, until it
+/// fills the box in one dimension. This sets `dpi` to 72 so that one point is
+/// one pixel, `size` to the box in inches with `!` to allow scaling up, and
+/// `pad` to 0 so no padding is added around the box. Since GraphViz reads these
+/// attributes during layout, the layout is computed here with `engine` and
+/// freed afterwards, replacing any layout the graph already had. The graph is
+/// borrowed mutably only for the duration of the render: the previous `dpi`,
+/// `pad` and `size` values are restored before returning.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to render
+/// * `engine` - The layout engine to use
+/// * `width_px` - The maximum width in pixels
+/// * `height_px` - The maximum height in pixels
+///
+/// # Returns
+///
+/// A Result containing the PNG bytes or an error
+pub fn render_png_sized(
+    context: &Context,
+    graph: &mut Graph,
+    engine: layout::Engine,
+    width_px: u32,
+    height_px: u32,
+) -> Result<Vec<u8>, GraphvizError> {
+    const POINTS_PER_INCH: f64 = 72.0;
+    const SIZING_ATTRIBUTES: [&str; 3] = ["dpi", "pad", "size"];
+    
+    let mut previous = Vec::with_capacity(SIZING_ATTRIBUTES.len());
+    for name in SIZING_ATTRIBUTES {
+        previous.push((name, graph.get_attribute(name)?.unwrap_or_default()));
     }
+    
+    let rendered = (|| {
+        graph.set_attribute("dpi", &POINTS_PER_INCH.to_string())?;
+        graph.set_attribute("pad", "0")?;
+        graph.set_attribute("size", &format!(
+            "{},{}!",
+            f64::from(width_px) / POINTS_PER_INCH,
+            f64::from(height_px) / POINTS_PER_INCH,
+        ))?;
+        
+        layout::apply_layout(context, graph, engine)?;
+        let rendered = render_to_bytes(context, graph, Format::Png);
+        layout::free_layout(context, graph)?;
+        rendered
+    })();
+    
+    for (name, value) in previous {
+        graph.set_attribute(name, &value)?;
+    }
+    
+    rendered
+}
+
+/// Lays out and renders a single subgraph or cluster as a standalone image.
+///
+/// The subgraph is first copied into its own root graph (see
+/// `Subgraph::to_graph`), so it is laid out at full size without the rest of
+/// the graph; edges leaving the subgraph are not drawn. The original graph
+/// and any layout it has are left untouched.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `subgraph` - The subgraph to render
+/// * `engine` - The layout engine to use
+/// * `format` - The output format
+///
+/// # Returns
+///
+/// A Result containing the rendered bytes or an error
+pub fn render_subgraph_to_bytes(
+    context: &Context,
+    subgraph: &Subgraph,
+    engine: layout::Engine,
+    format: Format,
+) -> Result<Vec<u8>, GraphvizError> {
+    let mut graph = subgraph.to_graph()?;
+    layout::apply_layout(context, &mut graph, engine)?;
+    let rendered = render_to_bytes(context, &graph, format);
+    layout::free_layout(context, &mut graph)?;
+    rendered
+}
+
+/// Renders a paginated graph to a multi-page PostScript or PDF file.
+///
+/// Set the page size with `LayoutSettings::with_page` before layout.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to render
+/// * `format` - The output format, `Format::Ps` or `Format::Pdf`
+/// * `path` - The output file path
+///
+/// # Returns
+///
+/// A Result indicating success, or `GraphvizError::InvalidFormat` for formats
+/// that cannot hold multiple pages
+pub fn render_pages_to_file<P: AsRef<Path>>(
+    context: &Context,
+    graph: &Graph,
+    format: Format,
+    path: P,
+) -> Result<(), GraphvizError> {
+    if !matches!(format, Format::Ps | Format::Pdf) {
+        return Err(GraphvizError::InvalidFormat);
+    }
+    
+    render_to_file(context, graph, format, path)
+}
+
+/// Renders a graph to a file, creating any missing parent directories first.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to render
+/// * `format` - The output format
+/// * `path` - The output file path
+///
+/// # Returns
+///
+/// A Result indicating success or failure
+pub fn render_to_file_create_dirs<P: AsRef<Path>>(
+    context: &Context,
+    graph: &Graph,
+    format: Format,
+    path: P,
+) -> Result<(), GraphvizError> {
+    if let Some(parent) = path.as_ref().parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    
+    render_to_file(context, graph, format, path)
 }
 
 /// Renders a graph to a string with the specified format.
@@ -250,7 +511,7 @@ pub fn render_to_string(
     let mut length: usize = 0;
     
     // Call GraphViz rendering function to generate in-memory representation
-    let result = unsafe {
+    let result = layout::serialized(|| unsafe {
         sys::gvRenderData(
             context.inner,
             graph.inner,
@@ -258,11 +519,11 @@ pub fn render_to_string(
             &mut buffer_ptr,
             &mut length,
         )
-    };
+    });
     
     // Validate rendering operation completed successfully
     if result != 0 {
-        return Err(GraphvizError::RenderFailed);
+        return Err(render_error(context, &format_cstr));
     }
     
     // Ensure buffer was allocated properly
@@ -270,17 +531,23 @@ pub fn render_to_string(
         return Err(GraphvizError::NullPointer("Render buffer is null"));
     }
     
+    // Refuse lengths a slice cannot address instead of truncating them
+    if length > isize::MAX as usize {
+        unsafe { sys::gvFreeRenderData(buffer_ptr) };
+        return Err(GraphvizError::RenderTooLarge(length));
+    }
+    
     // Convert data to Rust string, handling different formats appropriately
     let rendered_string = if format.is_binary() {
         // For binary formats, encode as base64
         let data_slice = unsafe { 
-            slice::from_raw_parts(buffer_ptr as *const u8, length as usize) 
+            slice::from_raw_parts(buffer_ptr as *const u8, length) 
         };
         base64::engine::general_purpose::STANDARD.encode(data_slice)
     } else {
         // For text formats, convert directly to UTF-8 string
         let data_slice = unsafe { 
-            slice::from_raw_parts(buffer_ptr as *const u8, length as usize) 
+            slice::from_raw_parts(buffer_ptr as *const u8, length) 
         };
         match str::from_utf8(data_slice) {
             Ok(s) => s.to_owned(),
@@ -314,15 +581,44 @@ pub fn render_to_bytes(
     graph: &Graph,
     format: Format,
 ) -> Result<Vec<u8>, GraphvizError> {
-    // Convert format to C string representation
-    let format_cstr = format.as_cstr()?;
-    
+    render_data(context, graph, &format.as_cstr()?)
+}
+
+/// Renders a graph to a byte vector with the specified format and renderer.
+///
+/// See `render_to_file_with_renderer` for how renderers are selected.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to render
+/// * `format` - The output format
+/// * `renderer` - The renderer suffix, e.g. "cairo", "gd" or "core"
+///
+/// # Returns
+///
+/// A Result containing the rendered bytes or an error
+pub fn render_to_bytes_with_renderer(
+    context: &Context,
+    graph: &Graph,
+    format: Format,
+    renderer: &str,
+) -> Result<Vec<u8>, GraphvizError> {
+    render_data(context, graph, &format.as_cstr_with_renderer(renderer)?)
+}
+
+/// Renders a graph to a byte vector using a GraphViz format string.
+fn render_data(
+    context: &Context,
+    graph: &Graph,
+    format_cstr: &CStr,
+) -> Result<Vec<u8>, GraphvizError> {
     // Prepare pointers to receive rendered data and length
     let mut buffer_ptr: *mut std::os::raw::c_char = std::ptr::null_mut();
     let mut length: usize = 0;
     
     // Call GraphViz rendering function to generate in-memory representation
-    let result = unsafe {
+    let result = layout::serialized(|| unsafe {
         sys::gvRenderData(
             context.inner,
             graph.inner,
@@ -330,11 +626,11 @@ pub fn render_to_bytes(
             &mut buffer_ptr,
             &mut length,
         )
-    };
+    });
     
     // Validate rendering operation completed successfully
     if result != 0 {
-        return Err(GraphvizError::RenderFailed);
+        return Err(render_error(context, format_cstr));
     }
     
     // Ensure buffer was allocated properly
@@ -342,9 +638,15 @@ pub fn render_to_bytes(
         return Err(GraphvizError::NullPointer("Render buffer is null"));
     }
     
+    // Refuse lengths a slice cannot address instead of truncating them
+    if length > isize::MAX as usize {
+        unsafe { sys::gvFreeRenderData(buffer_ptr) };
+        return Err(GraphvizError::RenderTooLarge(length));
+    }
+    
     // Copy data into a Vec<u8>
     let data_slice = unsafe { 
-        slice::from_raw_parts(buffer_ptr as *const u8, length as usize) 
+        slice::from_raw_parts(buffer_ptr as *const u8, length) 
     };
     let bytes = data_slice.to_vec();
     
@@ -354,8 +656,26 @@ pub fn render_to_bytes(
     Ok(bytes)
 }
 
+/// Classifies a failed render, distinguishing formats no installed plugin provides.
+fn render_error(context: &Context, format_cstr: &CStr) -> GraphvizError {
+    let format = format_cstr.to_string_lossy();
+    let name = format.split(':').next().unwrap_or_default();
+    let available = context.plugin_list("device")
+        .map(|devices| devices.iter().any(|device| device == name))
+        .unwrap_or(true);
+    
+    if available {
+        GraphvizError::RenderFailed
+    } else {
+        GraphvizError::UnsupportedFormat(format.into_owned())
+    }
+}
+
 /// Renders a graph to a writer with the specified format.
 ///
+/// The writer is flushed after the output is written, so buffered writers
+/// never hold back part of the rendering.
+///
 /// # Arguments
 ///
 /// * `context` - The GraphViz context
@@ -365,16 +685,334 @@ pub fn render_to_bytes(
 ///
 /// # Returns
 ///
-/// A Result indicating success or failure
+/// A Result containing the number of bytes written or an error
 pub fn render_to_writer<W: Write>(
     context: &Context,
     graph: &Graph,
     format: Format,
     mut writer: W,
-) -> Result<(), GraphvizError> {
+) -> Result<usize, GraphvizError> {
     let bytes = render_to_bytes(context, graph, format)?;
     writer.write_all(&bytes)?;
-    Ok(())
+    writer.flush()?;
+    Ok(bytes.len())
+}
+
+/// Renders a graph to GraphViz JSON and parses it into a JSON value.
+///
+/// The value contains the computed layout geometry (`objects`, `edges`, `bb`, ...),
+/// so a layout must have been applied first. Requires the `serde` feature.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to render
+///
+/// # Returns
+///
+/// A Result containing the parsed JSON value or an error
+#[cfg(feature = "serde")]
+pub fn render_to_json_value(
+    context: &Context,
+    graph: &Graph,
+) -> Result<serde_json::Value, GraphvizError> {
+    let json = render_to_string(context, graph, Format::Json)?;
+    serde_json::from_str(&json).map_err(|_| GraphvizError::InvalidJson)
+}
+
+/// A font stack available on virtually every platform, for use with `render_svg_portable`.
+pub const PORTABLE_FONT_STACK: &str = "Helvetica, Arial, sans-serif";
+
+/// Renders a graph to SVG with every `font-family` replaced by a fallback stack.
+///
+/// GraphViz writes the font names it resolved on the rendering machine into
+/// the SVG, so the same file can look different elsewhere. Rewriting them to
+/// a common stack such as `PORTABLE_FONT_STACK` makes the output render the
+/// same across machines.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to render
+/// * `font_stack` - The CSS font stack to use for all text
+///
+/// # Returns
+///
+/// A Result containing the rewritten SVG or an error
+pub fn render_svg_portable(
+    context: &Context,
+    graph: &Graph,
+    font_stack: &str,
+) -> Result<String, GraphvizError> {
+    let svg = render_to_string(context, graph, Format::Svg)?;
+    let font_stack = font_stack
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;");
+    
+    let marker = "font-family=\"";
+    let mut portable = String::with_capacity(svg.len());
+    let mut rest = svg.as_str();
+    
+    while let Some(start) = rest.find(marker) {
+        let value_start = start + marker.len();
+        
+        match rest[value_start..].find('"') {
+            Some(value_len) => {
+                portable.push_str(&rest[..value_start]);
+                portable.push_str(&font_stack);
+                rest = &rest[value_start + value_len..];
+            }
+            None => break,
+        }
+    }
+    
+    portable.push_str(rest);
+    Ok(portable)
+}
+
+/// Renders a graph to SVG and returns it along with the drawing's size.
+///
+/// The size is read from the bounding box (`bb`) GraphViz computed during
+/// layout, so the graph must already be laid out. It is given in points
+/// (1/72 inch) and covers the drawing itself; the SVG's `width` and `height`
+/// additionally include the `pad` margin (4 points on each side by default)
+/// and any scaling requested through `size`.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The laid-out graph to render
+///
+/// # Returns
+///
+/// A Result containing the SVG and its (width, height) in points, or
+/// `GraphvizError::LayoutFailed` if the graph has no bounding box
+pub fn render_svg_with_size(
+    context: &Context,
+    graph: &Graph,
+) -> Result<(String, (f64, f64)), GraphvizError> {
+    let svg = render_to_string(context, graph, Format::Svg)?;
+    let bb = graph.get_attribute("bb")?.ok_or(GraphvizError::LayoutFailed)?;
+    let coords = bb.split(',')
+        .map(|value| value.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| GraphvizError::LayoutFailed)?;
+    
+    match coords[..] {
+        [llx, lly, urx, ury] => Ok((svg, (urx - llx, ury - lly))),
+        _ => Err(GraphvizError::LayoutFailed),
+    }
+}
+
+/// A clickable region parsed from a client-side image map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapArea {
+    /// Shape of the region: `rect`, `circle` or `poly`.
+    pub shape: String,
+    /// Pixel coordinates of the region, interpreted according to `shape`.
+    pub coords: Vec<i32>,
+    /// Link target, from the element's `href` or `URL` attribute.
+    pub href: Option<String>,
+    /// Tooltip text, from the element's `tooltip` or label.
+    pub title: Option<String>,
+}
+
+/// Renders a graph as a client-side image map and parses its regions.
+///
+/// Only elements with a `URL`, `href` or `tooltip` attribute produce regions,
+/// and a layout must have been applied first.
+///
+/// # Arguments
+///
+/// * `context` - The GraphViz context
+/// * `graph` - The graph to render
+///
+/// # Returns
+///
+/// A Result containing the regions in document order or an error
+pub fn render_image_map(
+    context: &Context,
+    graph: &Graph,
+) -> Result<Vec<MapArea>, GraphvizError> {
+    let map = render_to_string(context, graph, Format::Cmapx)?;
+    let mut areas = Vec::new();
+    let mut rest = map.as_str();
+    
+    while let Some(start) = rest.find("<area ") {
+        let tag_end = rest[start..].find('>').map_or(rest.len(), |end| start + end);
+        let tag = &rest[start..tag_end];
+        
+        let coords = area_attribute(tag, "coords")
+            .unwrap_or_default()
+            .split([',', ' '])
+            .filter(|coord| !coord.is_empty())
+            .map(|coord| coord.parse::<i32>().map_err(|_| GraphvizError::RenderFailed))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        areas.push(MapArea {
+            shape: area_attribute(tag, "shape").unwrap_or_default(),
+            coords,
+            href: area_attribute(tag, "href"),
+            title: area_attribute(tag, "title"),
+        });
+        
+        rest = &rest[tag_end..];
+    }
+    
+    Ok(areas)
+}
+
+/// Extracts and unescapes a double-quoted attribute from an HTML tag.
+fn area_attribute(tag: &str, name: &str) -> Option<String> {
+    let marker = format!(" {}=\"", name);
+    let start = tag.find(&marker)? + marker.len();
+    let len = tag[start..].find('"')?;
+    
+    Some(unescape_html(&tag[start..start + len]))
+}
+
+/// Replaces the character references GraphViz emits in image maps.
+fn unescape_html(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                    .and_then(|code| code.ok())
+                    .and_then(char::from_u32),
+            };
+            ch.map(|ch| (ch, end + 1))
+        });
+        
+        match decoded {
+            Some((ch, len)) => {
+                unescaped.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Key identifying a rendering in a `RenderCache`.
+type CacheKey = (layout::Engine, Format, Vec<u8>);
+
+/// A memo of rendered output keyed by graph content, for services that
+/// repeatedly render identical graphs.
+///
+/// A graph is identified by its canonical DOT text (`Format::Canon`)
+/// together with the layout engine and output format, so graphs built
+/// separately with the same nodes, edges and attributes share an entry.
+/// The full canonical text is the key, so distinct graphs never collide.
+/// Entries are kept until `clear` is called. The cache can be shared
+/// between threads.
+#[derive(Debug, Default)]
+pub struct RenderCache {
+    /// Rendered output by engine, format and canonical DOT text
+    entries: Mutex<HashMap<CacheKey, Vec<u8>>>,
+}
+
+// RenderCache implementation
+impl RenderCache {
+    /// Creates an empty cache.
+    ///
+    /// # Returns
+    ///
+    /// A new RenderCache
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Lays out and renders a graph, reusing the output of an identical earlier call.
+    ///
+    /// On a miss, the graph is laid out, rendered with `render_to_bytes` and
+    /// the layout freed again, as `render_graph` does. On a hit, neither
+    /// layout nor rendering runs. Layout attributes such as `bb` that an
+    /// earlier layout left on the graph are part of its content, so render
+    /// freshly built graphs for the best hit rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The GraphViz context
+    /// * `graph` - The graph to render
+    /// * `engine` - The layout engine to use
+    /// * `format` - The output format
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the rendered bytes or an error
+    pub fn render(
+        &self,
+        context: &Context,
+        graph: &mut Graph,
+        engine: layout::Engine,
+        format: Format,
+    ) -> Result<Vec<u8>, GraphvizError> {
+        let canonical = render_data(context, graph, &Format::Canon.as_cstr()?)?;
+        let key = (engine, format, canonical);
+        
+        if let Some(rendered) = self.entries().get(&key) {
+            return Ok(rendered.clone());
+        }
+        
+        // Render without holding the lock so other threads are not blocked
+        layout::apply_layout(context, graph, engine)?;
+        let rendered = render_to_bytes(context, graph, format);
+        layout::free_layout(context, graph)?;
+        let rendered = rendered?;
+        
+        self.entries().insert(key, rendered.clone());
+        Ok(rendered)
+    }
+    
+    /// Gets the number of cached renderings.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+    
+    /// Checks whether the cache holds no renderings.
+    ///
+    /// # Returns
+    ///
+    /// true if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+    
+    /// Removes all cached renderings.
+    pub fn clear(&self) {
+        self.entries().clear();
+    }
+    
+    /// Locks the entries, recovering them if another thread panicked while holding the lock.
+    fn entries(&self) -> MutexGuard<'_, HashMap<CacheKey, Vec<u8>>> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 }
 
 /// Options for rendering graphs.
@@ -497,6 +1135,11 @@ impl RenderOptions {
     
     /// Sets the resolution in DPI.
     ///
+    /// GraphViz reads `dpi` when the layout is computed, so these options must
+    /// be applied before `apply_layout` for the resolution to take effect. To
+    /// keep layout configuration in one place, prefer
+    /// `LayoutSettings::with_dpi`.
+    ///
     /// # Arguments
     ///
     /// * `dpi` - The resolution value
@@ -0,0 +1,35 @@
+use vizoxide::attr::{quote_id, AttributeContainer};
+use vizoxide::{Graph, GraphvizError};
+
+/// Parses a one-node graph whose node name is written with `quote_id`.
+fn parse_node_name(name: &str) -> Result<Vec<String>, GraphvizError> {
+    let dot = format!("digraph g {{ {}; }}", quote_id(name)?);
+    let graph = Graph::from_dot_reader(dot.as_bytes())?;
+    
+    graph.nodes().map(|node| node.name()).collect()
+}
+
+#[test]
+fn set_label_checked_round_trips_multibyte_labels() {
+    let graph = Graph::new("g", true).unwrap();
+    let node = graph.add_node("n").unwrap();
+    
+    for label in ["Café crème", "Ærøskøbing – Ñandú", "🚀 launch 🎉", "数据 👩‍💻"] {
+        node.set_label_checked(label).unwrap();
+        assert_eq!(node.get_attribute("label").unwrap().as_deref(), Some(label));
+    }
+}
+
+#[test]
+fn quote_id_round_trips_through_the_dot_parser() {
+    for name in ["a:b", "a\"b", "a\\b", "a\\\\\"b", "a\\\\", "node"] {
+        assert_eq!(parse_node_name(name).unwrap(), [name]);
+    }
+}
+
+#[test]
+fn quote_id_rejects_odd_backslashes_before_quote_or_end() {
+    for name in ["a\\\"b", "a\\", "a\\\\\\"] {
+        assert!(matches!(quote_id(name), Err(GraphvizError::UnquotableId(id)) if id == name));
+    }
+}
@@ -0,0 +1,61 @@
+use vizoxide::attr::AttributeContainer;
+use vizoxide::diff::{AttributeChange, DiffElement};
+use vizoxide::Graph;
+
+#[test]
+fn diff_reports_added_and_removed_nodes() {
+    let old = Graph::from_edges("g", true, [("a", "b")]).unwrap();
+    let new = Graph::from_edges("g", true, [("b", "c")]).unwrap();
+    
+    let diff = old.diff(&new).unwrap();
+    
+    assert_eq!(diff.added_nodes, ["c"]);
+    assert_eq!(diff.removed_nodes, ["a"]);
+    assert_eq!(diff.added_edges, [("b".to_owned(), "c".to_owned())]);
+    assert_eq!(diff.removed_edges, [("a".to_owned(), "b".to_owned())]);
+    assert_eq!(diff.directed_changed, None);
+}
+
+#[test]
+fn diff_identifies_which_parallel_edge_changed() {
+    let build = |second_color: &str| {
+        let graph = Graph::new("g", true).unwrap();
+        let a = graph.add_node("a").unwrap();
+        let b = graph.add_node("b").unwrap();
+        graph.add_edge(&a, &b, None).unwrap().set_attribute("color", "red").unwrap();
+        graph.add_edge(&a, &b, None).unwrap().set_attribute("color", second_color).unwrap();
+        graph
+    };
+    let old = build("red");
+    let new = build("blue");
+    
+    let diff = old.diff(&new).unwrap();
+    
+    let element = DiffElement::Edge { from: "a".to_owned(), to: "b".to_owned(), name: None, index: 1 };
+    let change = AttributeChange {
+        name: "color".to_owned(),
+        old: Some("red".to_owned()),
+        new: Some("blue".to_owned()),
+    };
+    assert_eq!(diff.changed_attributes.len(), 1);
+    assert_eq!(diff.changed_attributes[&element], [change]);
+}
+
+#[test]
+fn diff_ignores_endpoint_order_in_undirected_graphs() {
+    let old = Graph::from_edges("g", false, [("a", "b")]).unwrap();
+    let new = Graph::from_edges("g", false, [("b", "a")]).unwrap();
+    
+    assert!(old.diff(&new).unwrap().is_empty());
+}
+
+#[test]
+fn diff_reports_changed_directedness() {
+    let old = Graph::from_edges("g", true, [("a", "b")]).unwrap();
+    let new = Graph::from_edges("g", false, [("a", "b")]).unwrap();
+    
+    let diff = old.diff(&new).unwrap();
+    
+    assert_eq!(diff.directed_changed, Some((true, false)));
+    assert!(diff.added_edges.is_empty() && diff.removed_edges.is_empty());
+}
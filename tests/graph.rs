@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+use vizoxide::{Graph, GraphvizError};
+use vizoxide::attr::AttributeContainer;
+
+/// Gets the sorted names of the given nodes.
+fn sorted_names<'a>(nodes: impl Iterator<Item = vizoxide::Node<'a>>) -> Vec<String> {
+    let mut names: Vec<_> = nodes.map(|node| node.name().unwrap()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn transpose_swaps_out_edges_and_in_edges() {
+    let graph = Graph::from_edges("g", true, [("a", "b"), ("a", "c"), ("b", "c"), ("c", "a")]).unwrap();
+    let transposed = graph.transpose().unwrap();
+    
+    assert_eq!(transposed.node_count(), graph.node_count());
+    assert_eq!(transposed.edge_count(), graph.edge_count());
+    
+    for node in graph.nodes() {
+        let copy = transposed.get_node(&node.name().unwrap()).unwrap().unwrap();
+        let sources = sorted_names(graph.in_edges(&node).map(|edge| edge.from_node()));
+        let targets = sorted_names(transposed.out_edges(&copy).map(|edge| edge.to_node()));
+        
+        assert_eq!(targets, sources);
+    }
+}
+
+#[test]
+fn filter_nodes_keeps_matching_category() {
+    let graph = Graph::from_edges("g", true, [("db", "cache"), ("cache", "web"), ("db", "web"), ("web", "cdn")]).unwrap();
+    
+    for (name, category) in [("db", "storage"), ("cache", "storage"), ("web", "frontend"), ("cdn", "frontend")] {
+        graph.get_node(name).unwrap().unwrap().set_attribute("category", category).unwrap();
+    }
+    
+    let storage = graph
+        .filter_nodes(|node| node.get_attribute("category").unwrap().as_deref() == Some("storage"))
+        .unwrap();
+    
+    assert_eq!(sorted_names(storage.nodes()), ["cache", "db"]);
+    assert_eq!(storage.edge_endpoints().unwrap(), [("db".to_owned(), "cache".to_owned())]);
+}
+
+#[test]
+fn for_each_node_sets_color_on_every_node() {
+    let graph = Graph::from_edges("g", true, [("a", "b"), ("b", "c")]).unwrap();
+    
+    graph.for_each_node(|node| node.set_attribute("color", "red")).unwrap();
+    
+    for node in graph.nodes() {
+        assert_eq!(node.get_attribute("color").unwrap().as_deref(), Some("red"));
+    }
+}
+
+#[test]
+fn parallel_edges_collapse_to_one() {
+    let graph = Graph::from_edges("g", true, [("a", "b"), ("b", "c")]).unwrap();
+    let (a, b) = (graph.get_node("a").unwrap().unwrap(), graph.get_node("b").unwrap().unwrap());
+    graph.add_edge(&a, &b, None).unwrap();
+    assert_eq!(graph.edge_count(), 3);
+    
+    let strict = graph.to_strict().unwrap();
+    assert!(strict.is_strict());
+    assert_eq!(strict.edge_count(), 2);
+    
+    let collapsed = graph
+        .collapse_parallel_edges(|edges| {
+            let mut attributes = HashMap::new();
+            attributes.insert("weight".to_owned(), edges.len().to_string());
+            attributes
+        })
+        .unwrap();
+    let a = collapsed.get_node("a").unwrap().unwrap();
+    let b = collapsed.get_node("b").unwrap().unwrap();
+    let merged = collapsed.edges_between(&a, &b);
+    
+    assert_eq!(collapsed.edge_count(), 2);
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].get_attribute("weight").unwrap().as_deref(), Some("2"));
+}
+
+#[test]
+fn three_cycle_from_edges_has_expected_stats() {
+    let graph = Graph::from_edges("g", true, [("a", "b"), ("b", "c"), ("c", "a"), ("a", "b")]).unwrap();
+    let stats = graph.stats().unwrap();
+    
+    assert_eq!(stats.node_count, 3);
+    assert_eq!(stats.edge_count, 3);
+    assert!(!stats.acyclic);
+    assert_eq!(stats.connected_components, 1);
+}
+
+#[test]
+fn edges_between_returns_parallel_edges() {
+    let graph = Graph::new("g", true).unwrap();
+    let a = graph.add_node("a").unwrap();
+    let b = graph.add_node("b").unwrap();
+    graph.add_edge(&a, &b, None).unwrap();
+    graph.add_edge(&a, &b, None).unwrap();
+    graph.add_edge(&b, &a, None).unwrap();
+    
+    assert_eq!(graph.edges_between(&a, &b).len(), 2);
+    assert_eq!(graph.shortest_path(&a, &b).map(|path| path.len()), Some(2));
+}
+
+#[test]
+fn adjacent_pairs_expose_edge_label() {
+    let graph = Graph::new("g", true).unwrap();
+    let a = graph.add_node("a").unwrap();
+    let b = graph.add_node("b").unwrap();
+    graph.add_edge(&a, &b, None).unwrap().set_attribute("label", "depends on").unwrap();
+    
+    let pairs: Vec<_> = graph.adjacent(&a).collect();
+    
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].0.name().unwrap(), "b");
+    assert_eq!(pairs[0].1.get_attribute("label").unwrap().as_deref(), Some("depends on"));
+}
+
+#[test]
+fn transitive_closure_and_reduction_of_chain() {
+    let chain = Graph::from_edges("g", true, [("a", "b"), ("b", "c")]).unwrap();
+    let shortcut = ("a".to_owned(), "c".to_owned());
+    
+    let closure = chain.transitive_closure().unwrap();
+    assert_eq!(closure.edge_count(), 3);
+    assert!(closure.edge_endpoints().unwrap().contains(&shortcut));
+    
+    let reduction = closure.transitive_reduction().unwrap();
+    assert_eq!(reduction.edge_count(), 2);
+    assert!(!reduction.edge_endpoints().unwrap().contains(&shortcut));
+}
+
+#[test]
+fn add_edge_rejects_node_from_another_graph() {
+    let graph = Graph::new("g", true).unwrap();
+    let other = Graph::new("other", true).unwrap();
+    let a = graph.add_node("a").unwrap();
+    let stranger = other.add_node("stranger").unwrap();
+    
+    let result = graph.add_edge(&a, &stranger, None);
+    
+    assert!(matches!(result, Err(GraphvizError::ForeignNode(name)) if name == "stranger"));
+    assert_eq!(graph.edge_count(), 0);
+    assert_eq!(graph.node_count(), 1);
+}
+
+#[test]
+fn align_group_places_nodes_on_same_rank() {
+    let graph = Graph::from_edges("g", true, [("root", "a"), ("root", "b")]).unwrap();
+    let a = graph.get_node("a").unwrap().unwrap();
+    let b = graph.get_node("b").unwrap().unwrap();
+    
+    let group = graph.align_group().add(&a).add(&b).same_rank().unwrap();
+    
+    assert_eq!(group.get_attribute("rank").unwrap().as_deref(), Some("same"));
+    assert_eq!(sorted_names(group.nodes()), ["a", "b"]);
+}
+
+#[test]
+fn align_group_with_foreign_node_creates_nothing() {
+    let graph = Graph::new("g", true).unwrap();
+    let other = Graph::new("other", true).unwrap();
+    let a = graph.add_node("a").unwrap();
+    let stranger = other.add_node("stranger").unwrap();
+    
+    let result = graph.align_group().add(&a).add(&stranger).same_rank();
+    
+    assert!(matches!(result, Err(GraphvizError::ForeignNode(_))));
+    assert_eq!(graph.subgraphs().count(), 0);
+}
+
+#[test]
+fn rename_node_keeps_edges_and_cluster_membership() {
+    let graph = Graph::from_edges("g", true, [("a", "b"), ("c", "a")]).unwrap();
+    let a = graph.get_node("a").unwrap().unwrap();
+    a.set_attribute("shape", "box").unwrap();
+    let cluster = graph.add_subgraph("cluster_x").unwrap();
+    cluster.add_node("a").unwrap();
+    cluster.add_node("b").unwrap();
+    
+    let renamed = graph.rename_node(&a, "z").unwrap();
+    
+    assert_eq!(renamed.get_attribute("shape").unwrap().as_deref(), Some("box"));
+    assert!(graph.get_node("a").unwrap().is_none());
+    assert_eq!(sorted_names(cluster.nodes()), ["b", "z"]);
+    
+    let mut edges = graph.edge_endpoints().unwrap();
+    edges.sort();
+    assert_eq!(edges, [("c".to_owned(), "z".to_owned()), ("z".to_owned(), "b".to_owned())]);
+}
+
+#[test]
+fn rename_node_to_taken_name_leaves_graph_unchanged() {
+    let graph = Graph::from_edges("g", true, [("a", "b")]).unwrap();
+    let a = graph.get_node("a").unwrap().unwrap();
+    
+    let result = graph.rename_node(&a, "b");
+    
+    assert!(matches!(result, Err(GraphvizError::DuplicateName(name)) if name == "b"));
+    assert_eq!(sorted_names(graph.nodes()), ["a", "b"]);
+    assert_eq!(graph.edge_count(), 1);
+}
+
+#[test]
+fn reverse_edge_in_strict_graph_rejects_existing_reverse() {
+    let graph = Graph::builder("g").directed(true).strict(true).build().unwrap();
+    let a = graph.add_node("a").unwrap();
+    let b = graph.add_node("b").unwrap();
+    let forward = graph.add_edge(&a, &b, None).unwrap();
+    graph.add_edge(&b, &a, None).unwrap();
+    
+    let result = graph.reverse_edge(forward);
+    
+    assert!(matches!(result, Err(GraphvizError::DuplicateEdge(from, to)) if from == "b" && to == "a"));
+    assert_eq!(graph.edge_count(), 2);
+}
+
+#[test]
+fn set_visible_toggles_invis_and_keeps_other_styles() {
+    let graph = Graph::new("g", true).unwrap();
+    let node = graph.add_node("a").unwrap();
+    node.set_attribute("style", "filled,setlinewidth(2)").unwrap();
+    
+    node.set_visible(false).unwrap();
+    assert_eq!(node.get_attribute("style").unwrap().as_deref(), Some("filled,setlinewidth(2),invis"));
+    
+    node.set_visible(true).unwrap();
+    assert_eq!(node.get_attribute("style").unwrap().as_deref(), Some("filled,setlinewidth(2)"));
+}
+
+/// A reader that returns its input a few bytes at a time, then fails if asked to.
+struct ChunkedReader<'a> {
+    input: &'a [u8],
+    fail_at_end: bool,
+}
+
+impl Read for ChunkedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.input.is_empty() && self.fail_at_end {
+            return Err(io::Error::new(io::ErrorKind::ConnectionReset, "stream cut off"));
+        }
+        
+        let count = buf.len().min(self.input.len()).min(3);
+        buf[..count].copy_from_slice(&self.input[..count]);
+        self.input = &self.input[count..];
+        Ok(count)
+    }
+}
+
+#[test]
+fn from_dot_reader_parses_chunked_input() {
+    let reader = ChunkedReader { input: b"digraph g { a -> b; b -> c [label=\"x\"]; }", fail_at_end: false };
+    
+    let graph = Graph::from_dot_reader(reader).unwrap();
+    
+    assert_eq!(sorted_names(graph.nodes()), ["a", "b", "c"]);
+    assert_eq!(graph.edge_count(), 2);
+}
+
+#[test]
+fn from_dot_reader_surfaces_reader_errors() {
+    let reader = ChunkedReader { input: b"digraph g { a -> ", fail_at_end: true };
+    
+    let result = Graph::from_dot_reader(reader);
+    
+    assert!(matches!(result, Err(GraphvizError::IoError(err)) if err.kind() == io::ErrorKind::ConnectionReset));
+}
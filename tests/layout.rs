@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use vizoxide::layout::{apply_layout, apply_layout_with_timeout, free_layout, Engine, LayoutSettings};
+use vizoxide::render::{render_to_string, Format};
+use vizoxide::{Context, Graph};
+
+#[test]
+fn layout_with_timeout_returns_graph_when_finished() {
+    let context = Context::new().unwrap();
+    let graph = Graph::new("small", true).unwrap();
+    let a = graph.add_node("a").unwrap();
+    let b = graph.add_node("b").unwrap();
+    graph.add_edge(&a, &b, None).unwrap();
+    
+    let (_context, graph) =
+        apply_layout_with_timeout(context, graph, Engine::Dot, Duration::from_secs(60)).unwrap();
+    
+    assert_eq!(graph.node_count(), 2);
+}
+
+/// Lays out two parallel edges and counts the distinct edge paths drawn.
+fn distinct_edge_paths(concentrate: bool) -> usize {
+    let context = Context::new().unwrap();
+    let mut graph = Graph::new("g", true).unwrap();
+    let a = graph.add_node("a").unwrap();
+    let b = graph.add_node("b").unwrap();
+    graph.add_edge(&a, &b, None).unwrap();
+    graph.add_edge(&a, &b, None).unwrap();
+    LayoutSettings::new().with_concentrate(concentrate).apply(&graph).unwrap();
+    
+    apply_layout(&context, &mut graph, Engine::Dot).unwrap();
+    let svg = render_to_string(&context, &graph, Format::Svg).unwrap();
+    
+    let paths: HashSet<_> = svg
+        .split("<path ")
+        .skip(1)
+        .filter_map(|path| path.split(" d=\"").nth(1))
+        .filter_map(|rest| rest.split('"').next())
+        .collect();
+    paths.len()
+}
+
+#[test]
+fn concentrate_merges_parallel_edges_in_dot() {
+    assert_eq!(distinct_edge_paths(false), 2);
+    assert_eq!(distinct_edge_paths(true), 1);
+}
+
+#[test]
+fn random_seed_is_not_left_on_the_graph() {
+    let context = Context::new().unwrap();
+    let mut graph = Graph::from_edges("g", false, [("a", "b"), ("b", "c"), ("c", "d"), ("d", "a"), ("a", "c")]).unwrap();
+    let mut layout_with_seed = |seed| {
+        context.set_random_seed(seed);
+        apply_layout(&context, &mut graph, Engine::Neato).unwrap();
+        let positions = graph.dump_layout().unwrap();
+        free_layout(&context, &mut graph).unwrap();
+        
+        assert_eq!(graph.get_attribute("start").unwrap().as_deref(), Some(""));
+        positions
+    };
+    
+    let first = layout_with_seed(1);
+    let second = layout_with_seed(2);
+    let repeated = layout_with_seed(1);
+    
+    assert_ne!(first, second);
+    assert_eq!(first, repeated);
+}
+
+#[test]
+fn compute_layout_reports_endpoints_of_parallel_edges() {
+    let context = Context::new().unwrap();
+    let mut graph = Graph::new("g", true).unwrap();
+    let a = graph.add_node("a").unwrap();
+    let b = graph.add_node("b").unwrap();
+    graph.add_edge(&a, &b, Some("first")).unwrap();
+    graph.add_edge(&a, &b, Some("second")).unwrap();
+    
+    let layout = context.compute_layout(&mut graph, Engine::Dot).unwrap();
+    
+    assert_eq!(layout.edges.len(), 2);
+    for spline in &layout.edges {
+        assert_eq!((spline.from.as_str(), spline.to.as_str()), ("a", "b"));
+        assert!(!spline.points.is_empty());
+    }
+}
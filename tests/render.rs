@@ -0,0 +1,71 @@
+use std::fs;
+
+use vizoxide::attr::AttributeContainer;
+use vizoxide::layout::{apply_layout, Engine};
+use vizoxide::render::{render_png_sized, render_to_string, Format};
+use vizoxide::{render_graph, Context, Graph};
+
+#[test]
+fn render_graph_writes_two_node_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("two_nodes.svg");
+    let mut graph = Graph::from_edges("g", true, [("a", "b")]).unwrap();
+    
+    render_graph(&mut graph, Engine::Dot, Format::Svg, &path).unwrap();
+    
+    let svg = fs::read_to_string(&path).unwrap();
+    assert!(svg.contains("<svg"));
+    assert!(svg.contains("<title>a</title>"));
+    assert!(svg.contains("<title>b</title>"));
+}
+
+#[test]
+fn external_label_appears_in_output() {
+    let context = Context::new().unwrap();
+    let mut graph = Graph::new("g", true).unwrap();
+    let a = graph.create_node("a").external_label("42 ms").build().unwrap();
+    let b = graph.add_node("b").unwrap();
+    graph.add_edge(&a, &b, None).unwrap();
+    graph.set_force_labels(true).unwrap();
+    
+    apply_layout(&context, &mut graph, Engine::Dot).unwrap();
+    let svg = render_to_string(&context, &graph, Format::Svg).unwrap();
+    
+    assert!(svg.contains(">42 ms</text>"));
+}
+
+#[test]
+fn link_url_is_stored_raw_and_escaped_once_in_svg() {
+    let url = "https://example.com/report?a=1&b=2";
+    let context = Context::new().unwrap();
+    let mut graph = Graph::new("g", true).unwrap();
+    let node = graph.create_node("a").link(url, None).build().unwrap();
+    
+    assert_eq!(node.get_attribute("URL").unwrap().as_deref(), Some(url));
+    
+    apply_layout(&context, &mut graph, Engine::Dot).unwrap();
+    let svg = render_to_string(&context, &graph, Format::Svg).unwrap();
+    
+    assert!(svg.contains("report?a=1&amp;b=2"));
+    assert_eq!(svg.matches('&').count(), 1);
+    assert_eq!(svg.matches("&amp;").count(), 1);
+}
+
+#[test]
+fn render_png_sized_fits_box_and_restores_attributes() {
+    let context = Context::new().unwrap();
+    let mut graph = Graph::from_edges("g", true, [("a", "b"), ("b", "c"), ("a", "c")]).unwrap();
+    graph.set_attribute("pad", "0.5").unwrap();
+    
+    let png = render_png_sized(&context, &mut graph, Engine::Dot, 200, 100).unwrap();
+    
+    assert_eq!(&png[1..4], b"PNG");
+    let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+    assert!(width > 0 && width <= 200, "width {width}");
+    assert!(height > 0 && height <= 100, "height {height}");
+    
+    assert_eq!(graph.get_attribute("pad").unwrap().as_deref(), Some("0.5"));
+    assert_eq!(graph.get_attribute("dpi").unwrap().as_deref(), Some(""));
+    assert_eq!(graph.get_attribute("size").unwrap().as_deref(), Some(""));
+}
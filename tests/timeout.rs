@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use vizoxide::layout::{apply_layout, apply_layout_with_timeout, wait_for_abandoned_layouts, Engine};
+use vizoxide::{Context, Graph, GraphvizError};
+
+/// Builds a graph large enough that its layout takes well over a millisecond.
+fn large_graph() -> Graph {
+    let graph = Graph::new("large", true).unwrap();
+    let nodes: Vec<_> = (0..400)
+        .map(|index| graph.add_node(&format!("n{}", index)).unwrap())
+        .collect();
+    
+    for (index, node) in nodes.iter().enumerate() {
+        for step in [1, 7, 31] {
+            graph.add_edge(node, &nodes[(index * step + 13) % nodes.len()], None).unwrap();
+        }
+    }
+    
+    graph
+}
+
+// Abandoned layouts must not overlap other tests that build graphs, so this
+// scenario runs alone in its own test binary.
+#[test]
+fn layout_after_timeout_waits_for_abandoned_layout() {
+    // Built up front: editing graphs is not serialized with the abandoned layout
+    let mut graph = Graph::new("after", true).unwrap();
+    let a = graph.add_node("a").unwrap();
+    let b = graph.add_node("b").unwrap();
+    graph.add_edge(&a, &b, None).unwrap();
+    
+    let context = Context::new().unwrap();
+    let result = apply_layout_with_timeout(context, large_graph(), Engine::Neato, Duration::from_millis(1));
+    
+    assert!(matches!(result, Err(GraphvizError::LayoutTimeout)));
+    
+    // Serialized behind the abandoned layout rather than racing with it
+    let context = Context::new().unwrap();
+    apply_layout(&context, &mut graph, Engine::Dot).unwrap();
+    
+    // Building graphs is safe again once the abandoned layout has been freed
+    wait_for_abandoned_layouts();
+    
+    let graph = Graph::from_edges("next", true, [("a", "b")]).unwrap();
+    assert_eq!(graph.node_count(), 2);
+}